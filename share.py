@@ -4,12 +4,25 @@ import platform
 # List of files to include in the state
 files = [
     "src-tauri/src/main.rs",
-    "src-tauri/src/game.rs",
-    "src-tauri/src/sprites.rs",
-    "src-tauri/src/traits.rs",
-    "src-tauri/src/constants.rs",
-    "src-tauri/src/collision.rs",
-    "src-tauri/src/game_objects.rs",
+    "src-tauri/src/simulation.rs",
+    "src-tauri/shepherd-core/src/lib.rs",
+    "src-tauri/shepherd-core/src/config.rs",
+    "src-tauri/shepherd-core/src/game.rs",
+    "src-tauri/shepherd-core/src/sprites.rs",
+    "src-tauri/shepherd-core/src/traits.rs",
+    "src-tauri/shepherd-core/src/constants.rs",
+    "src-tauri/shepherd-core/src/collision.rs",
+    "src-tauri/shepherd-core/src/broadphase.rs",
+    "src-tauri/shepherd-core/src/frames.rs",
+    "src-tauri/shepherd-core/src/events.rs",
+    "src-tauri/shepherd-core/src/phase.rs",
+    "src-tauri/shepherd-core/src/game_objects.rs",
+    "src-tauri/shepherd-core/src/high_scores.rs",
+    "src-tauri/shepherd-core/src/input.rs",
+    "src-tauri/shepherd-core/src/replay.rs",
+    "src-tauri/shepherd-core/src/timestep.rs",
+    "src-tauri/shepherd-headless/src/main.rs",
+    "src-tauri/shepherd-headless/src/script.rs",
     "src-tauri/tauri.conf.json",
     "src/routes/+layout.ts",
     "src/routes/+layout.svelte",
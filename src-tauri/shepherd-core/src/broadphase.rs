@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+// Uniform grid over object centres. Queries return a superset of the objects whose centre lies
+// within `radius`, so callers still run the exact narrow-phase check on each candidate.
+#[derive(Clone, Debug)]
+pub struct SpatialHash {
+    cell_size: f64,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f64) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    // Keeps the per-cell allocations around for the next rebuild
+    pub fn clear(&mut self) {
+        for indices in self.cells.values_mut() {
+            indices.clear();
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, (x, y): (f64, f64)) {
+        self.cells.entry(self.cell(x, y)).or_default().push(index);
+    }
+
+    // Candidate indices in ascending order, so callers see objects in the same order a linear
+    // scan would
+    pub fn query(&self, (x, y): (f64, f64), radius: f64) -> Vec<usize> {
+        let (min_x, min_y) = self.cell(x - radius, y - radius);
+        let (max_x, max_y) = self.cell(x + radius, y + radius);
+
+        let mut candidates = Vec::new();
+        for cell_x in min_x..=max_x {
+            for cell_y in min_y..=max_y {
+                if let Some(indices) = self.cells.get(&(cell_x, cell_y)) {
+                    candidates.extend_from_slice(indices);
+                }
+            }
+        }
+
+        candidates.sort_unstable();
+        candidates
+    }
+
+    fn cell(&self, x: f64, y: f64) -> (i64, i64) {
+        (
+            (x / self.cell_size).floor() as i64,
+            (y / self.cell_size).floor() as i64,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{
+        BROADPHASE_CELL_SIZE, CIRCLE_RADIUS, EXPLOSION_RADIUS, TRIANGLE_RADIUS, WINDOW_HEIGHT,
+        WINDOW_WIDTH,
+    };
+
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    // Anything within `radius` of the query must come back; extra candidates are fine
+    fn check_against_linear_scan(radius: f64) {
+        let mut rng = ChaCha8Rng::seed_from_u64(radius.to_bits());
+        let mut hash = SpatialHash::new(BROADPHASE_CELL_SIZE);
+
+        for _ in 0..50 {
+            // Objects can sit a little way past the edges, e.g. while flying in
+            let count = rng.gen_range(0..200);
+            let positions: Vec<(f64, f64)> = (0..count)
+                .map(|_| {
+                    (
+                        rng.gen_range(-200.0..WINDOW_WIDTH + 200.0),
+                        rng.gen_range(-200.0..WINDOW_HEIGHT + 200.0),
+                    )
+                })
+                .collect();
+
+            hash.clear();
+            for (index, &position) in positions.iter().enumerate() {
+                hash.insert(index, position);
+            }
+
+            for _ in 0..50 {
+                let (qx, qy) = (
+                    rng.gen_range(0.0..WINDOW_WIDTH),
+                    rng.gen_range(0.0..WINDOW_HEIGHT),
+                );
+                let within = |&index: &usize| {
+                    let (x, y) = positions[index];
+                    (x - qx).hypot(y - qy) <= radius
+                };
+
+                let candidates = hash.query((qx, qy), radius);
+                assert!(candidates.windows(2).all(|pair| pair[0] < pair[1]));
+
+                let expected: Vec<usize> = (0..positions.len()).filter(within).collect();
+                let found: Vec<usize> = candidates.into_iter().filter(within).collect();
+                assert_eq!(found, expected, "query at ({}, {})", qx, qy);
+            }
+        }
+    }
+
+    #[test]
+    fn explosion_query_matches_linear_scan() {
+        check_against_linear_scan(EXPLOSION_RADIUS);
+    }
+
+    #[test]
+    fn triangle_reach_query_matches_linear_scan() {
+        check_against_linear_scan(CIRCLE_RADIUS + TRIANGLE_RADIUS);
+    }
+}
@@ -1,10 +1,14 @@
-use crate::constants::CIRCLE_RADIUS;
+use crate::config::GameConfig;
 use crate::game::GameState;
 use crate::traits::Shape;
 
-pub fn check_edge_collision<S: Shape>(shape: &S, game_state: &GameState) -> bool {
+pub fn check_edge_collision<S: Shape>(
+    shape: &S,
+    game_state: &GameState,
+    config: &GameConfig,
+) -> bool {
     let (cx, cy) = game_state.player.get_sprite().get_coords();
-    let vertices = shape.get_vertices();
+    let vertices = shape.get_vertices(config);
 
     vertices
         .iter()
@@ -19,7 +23,7 @@ pub fn check_edge_collision<S: Shape>(shape: &S, game_state: &GameState) -> bool
             let b = 2.0 * (dx * (x1 - cx) + dy * (y1 - cy)); // Coefficient of t
             let c = x1 * x1 + y1 * y1 + cx * cx + cy * cy
                 - 2.0 * (x1 * cx + y1 * cy)
-                - CIRCLE_RADIUS * CIRCLE_RADIUS; // Constant term
+                - config.circle_radius * config.circle_radius; // Constant term
 
             // Discriminant of the quadratic equation
             let det = b * b - 4.0 * a * c;
@@ -30,13 +34,17 @@ pub fn check_edge_collision<S: Shape>(shape: &S, game_state: &GameState) -> bool
         })
 }
 
-pub fn check_corner_collision<S: Shape>(shape: &S, game_state: &GameState) -> bool {
+pub fn check_corner_collision<S: Shape>(
+    shape: &S,
+    game_state: &GameState,
+    config: &GameConfig,
+) -> bool {
     let (cx, cy) = game_state.player.get_sprite().get_coords();
-    let vertices = shape.get_vertices();
+    let vertices = shape.get_vertices(config);
 
     vertices.iter().any(|&(vx, vy)| {
         let dx = cx - vx;
         let dy = cy - vy;
-        dx * dx + dy * dy < CIRCLE_RADIUS * CIRCLE_RADIUS
+        dx * dx + dy * dy < config.circle_radius * config.circle_radius
     })
 }
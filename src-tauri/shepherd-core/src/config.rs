@@ -0,0 +1,342 @@
+use crate::constants::{
+    ANALOG_DEAD_ZONE, CIRCLE_RADIUS, COUNTDOWN_MS, DASH_COOLDOWN_MS, DASH_DURATION_MS,
+    DASH_INVULNERABLE, DASH_SPEED, DIAMOND_RADIUS, ENEMY_BUFFER_FRAC, ENEMY_SPAWN_INTERVAL,
+    ENEMY_SPEED, EXPLOSION_RADIUS, EXTRA_LIFE_SCORE, GATE_ARM_MS, GATE_BUFFER, GATE_SPAWN_INTERVAL,
+    MULTIPLIER_ATTRACT_MIN, MULTIPLIER_LIFETIME_MS, MULTIPLIER_SPEED, PLAYER_ACCELERATION,
+    PLAYER_FRICTION, PLAYER_SPEED, PUBLISH_RATE_HZ, RESPAWN_CLEAR_RADIUS, RESPAWN_INVULNERABLE_MS,
+    RESUME_ON_FOCUS, SQUARE_RADIUS, STARTING_LIVES, TICK_CYCLE_MS, TRIANGLE_RADIUS, WINDOW_HEIGHT,
+    WINDOW_WIDTH,
+};
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+// How the player responds to input
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Movement {
+    // Full speed the moment there is input and a dead stop without it
+    #[default]
+    Classic,
+    // Speeds up by `player_acceleration` towards what the input asks for, up to `player_speed`,
+    // and coasts to a stop under `player_friction`
+    Momentum,
+}
+
+// Tuning values for a game. Missing fields in a config file fall back to the defaults in
+// `constants.rs`, so a file only needs to list what it changes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameConfig {
+    pub window_width: f64,
+    pub window_height: f64,
+
+    pub player_speed: f64, // also the top speed with momentum
+    pub enemy_speed: f64,
+    pub movement: Movement,
+    pub player_acceleration: f64,
+    pub player_friction: f64,
+    pub dash_speed: f64,
+    pub dash_duration_ms: u64,
+    pub dash_cooldown_ms: u64,
+    pub dash_invulnerable: bool, // whether enemies and gate corners pass through a dashing player
+
+    pub starting_lives: u32,
+    pub extra_life_score: u64, // a life every this many points; 0 for none
+    pub respawn_invulnerable_ms: u64,
+    pub respawn_clear_radius: f64, // enemies this close to the death or the respawn are removed
+    pub analog_dead_zone: f64,     // stick magnitude below this doesn't move the player
+
+    pub tick_cycle_ms: u64,
+    pub publish_rate_hz: f64, // how often the frontend is sent a frame, independent of ticks
+    pub enemy_spawn_interval: u64,
+    pub gate_spawn_interval: u64,
+    pub gate_arm_ms: u64,
+    pub countdown_ms: u64,     // before each run; 0 starts straight away
+    pub resume_on_focus: bool, // after a pause caused by the window losing focus
+
+    pub circle_radius: f64,
+    pub diamond_radius: f64,
+    pub triangle_radius: f64,
+    pub square_radius: f64,
+
+    pub explosion_radius: f64,
+    pub gate_buffer: f64,
+    pub enemy_buffer_frac: f64,
+
+    pub multiplier_attract_min: f64,
+    pub multiplier_speed: f64,
+    pub multiplier_lifetime_ms: u64,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            window_width: WINDOW_WIDTH,
+            window_height: WINDOW_HEIGHT,
+            player_speed: PLAYER_SPEED,
+            enemy_speed: ENEMY_SPEED,
+            movement: Movement::default(),
+            player_acceleration: PLAYER_ACCELERATION,
+            player_friction: PLAYER_FRICTION,
+            dash_speed: DASH_SPEED,
+            dash_duration_ms: DASH_DURATION_MS,
+            dash_cooldown_ms: DASH_COOLDOWN_MS,
+            dash_invulnerable: DASH_INVULNERABLE,
+            starting_lives: STARTING_LIVES,
+            extra_life_score: EXTRA_LIFE_SCORE,
+            respawn_invulnerable_ms: RESPAWN_INVULNERABLE_MS,
+            respawn_clear_radius: RESPAWN_CLEAR_RADIUS,
+            analog_dead_zone: ANALOG_DEAD_ZONE,
+            tick_cycle_ms: TICK_CYCLE_MS,
+            publish_rate_hz: PUBLISH_RATE_HZ,
+            enemy_spawn_interval: ENEMY_SPAWN_INTERVAL,
+            gate_spawn_interval: GATE_SPAWN_INTERVAL,
+            gate_arm_ms: GATE_ARM_MS,
+            countdown_ms: COUNTDOWN_MS,
+            resume_on_focus: RESUME_ON_FOCUS,
+            circle_radius: CIRCLE_RADIUS,
+            diamond_radius: DIAMOND_RADIUS,
+            triangle_radius: TRIANGLE_RADIUS,
+            square_radius: SQUARE_RADIUS,
+            explosion_radius: EXPLOSION_RADIUS,
+            gate_buffer: GATE_BUFFER,
+            enemy_buffer_frac: ENEMY_BUFFER_FRAC,
+            multiplier_attract_min: MULTIPLIER_ATTRACT_MIN,
+            multiplier_speed: MULTIPLIER_SPEED,
+            multiplier_lifetime_ms: MULTIPLIER_LIFETIME_MS,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse(String),
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "failed to read config: {}", e),
+            ConfigError::Parse(e) => write!(f, "failed to parse config: {}", e),
+            ConfigError::Invalid(e) => write!(f, "invalid config: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(e: std::io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+impl GameConfig {
+    // `.json` files are read as JSON, anything else as TOML
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+
+        let config: GameConfig = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&source).map_err(|e| ConfigError::Parse(e.to_string()))?
+        } else {
+            toml::from_str(&source).map_err(|e| ConfigError::Parse(e.to_string()))?
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let positive = [
+            ("window_width", self.window_width),
+            ("window_height", self.window_height),
+            ("player_speed", self.player_speed),
+            ("enemy_speed", self.enemy_speed),
+            ("player_acceleration", self.player_acceleration),
+            ("dash_speed", self.dash_speed),
+            ("publish_rate_hz", self.publish_rate_hz),
+            ("circle_radius", self.circle_radius),
+            ("diamond_radius", self.diamond_radius),
+            ("triangle_radius", self.triangle_radius),
+            ("square_radius", self.square_radius),
+            ("explosion_radius", self.explosion_radius),
+            ("multiplier_attract_min", self.multiplier_attract_min),
+            ("multiplier_speed", self.multiplier_speed),
+        ];
+        for (name, value) in positive {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(ConfigError::Invalid(format!(
+                    "{} must be positive, got {}",
+                    name, value
+                )));
+            }
+        }
+
+        let nonzero = [
+            ("tick_cycle_ms", self.tick_cycle_ms),
+            ("enemy_spawn_interval", self.enemy_spawn_interval),
+            ("gate_spawn_interval", self.gate_spawn_interval),
+            ("multiplier_lifetime_ms", self.multiplier_lifetime_ms),
+            ("dash_duration_ms", self.dash_duration_ms),
+        ];
+        for (name, value) in nonzero {
+            if value == 0 {
+                return Err(ConfigError::Invalid(format!(
+                    "{} must be greater than 0",
+                    name
+                )));
+            }
+        }
+
+        if self.starting_lives == 0 {
+            return Err(ConfigError::Invalid(
+                "starting_lives must be at least 1".to_string(),
+            ));
+        }
+
+        if !(self.respawn_clear_radius.is_finite() && self.respawn_clear_radius >= 0.0) {
+            return Err(ConfigError::Invalid(format!(
+                "respawn_clear_radius must not be negative, got {}",
+                self.respawn_clear_radius
+            )));
+        }
+
+        if self.dash_cooldown_ms < self.dash_duration_ms {
+            return Err(ConfigError::Invalid(format!(
+                "dash_cooldown_ms {} is shorter than dash_duration_ms {}",
+                self.dash_cooldown_ms, self.dash_duration_ms
+            )));
+        }
+
+        // No friction would leave the player coasting forever
+        if !(self.player_friction > 0.0 && self.player_friction <= 1.0) {
+            return Err(ConfigError::Invalid(format!(
+                "player_friction must be in (0, 1], got {}",
+                self.player_friction
+            )));
+        }
+
+        // A dead zone of 1 would leave nothing to rescale the stick into
+        if !(self.analog_dead_zone >= 0.0 && self.analog_dead_zone < 1.0) {
+            return Err(ConfigError::Invalid(format!(
+                "analog_dead_zone must be in [0, 1), got {}",
+                self.analog_dead_zone
+            )));
+        }
+
+        // Spawn ranges must be non-empty or gen_range panics
+        if !(self.enemy_buffer_frac > 0.0 && self.enemy_buffer_frac <= 0.5) {
+            return Err(ConfigError::Invalid(format!(
+                "enemy_buffer_frac must be in (0, 0.5], got {}",
+                self.enemy_buffer_frac
+            )));
+        }
+
+        if !(self.gate_buffer >= 0.0
+            && 2.0 * self.gate_buffer < self.window_width.min(self.window_height))
+        {
+            return Err(ConfigError::Invalid(format!(
+                "gate_buffer {} leaves no room to spawn gates",
+                self.gate_buffer
+            )));
+        }
+
+        if 2.0 * self.circle_radius > self.window_width.min(self.window_height) {
+            return Err(ConfigError::Invalid(format!(
+                "circle_radius {} does not fit in the window",
+                self.circle_radius
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The error must name the field, so a broken config file is easy to fix
+    fn assert_rejects(field: &str, edit: impl Fn(&mut GameConfig)) {
+        let mut config = GameConfig::default();
+        edit(&mut config);
+        match config.validate() {
+            Err(ConfigError::Invalid(message)) => {
+                assert!(
+                    message.contains(field),
+                    "{:?} doesn't name {}",
+                    message,
+                    field
+                )
+            }
+            other => panic!("{} was accepted: {:?}", field, other),
+        }
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        GameConfig::default().validate().unwrap();
+    }
+
+    #[test]
+    fn rejects_sizes_and_speeds_that_are_not_positive() {
+        for value in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert_rejects("window_width", |c| c.window_width = value);
+            assert_rejects("player_speed", |c| c.player_speed = value);
+            assert_rejects("player_acceleration", |c| c.player_acceleration = value);
+            assert_rejects("dash_speed", |c| c.dash_speed = value);
+            assert_rejects("publish_rate_hz", |c| c.publish_rate_hz = value);
+            assert_rejects("triangle_radius", |c| c.triangle_radius = value);
+            assert_rejects("explosion_radius", |c| c.explosion_radius = value);
+            assert_rejects("multiplier_speed", |c| c.multiplier_speed = value);
+        }
+    }
+
+    #[test]
+    fn rejects_zero_intervals() {
+        assert_rejects("tick_cycle_ms", |c| c.tick_cycle_ms = 0);
+        assert_rejects("enemy_spawn_interval", |c| c.enemy_spawn_interval = 0);
+        assert_rejects("gate_spawn_interval", |c| c.gate_spawn_interval = 0);
+        assert_rejects("multiplier_lifetime_ms", |c| c.multiplier_lifetime_ms = 0);
+        assert_rejects("dash_duration_ms", |c| c.dash_duration_ms = 0);
+    }
+
+    #[test]
+    fn rejects_bad_lives_settings() {
+        assert_rejects("starting_lives", |c| c.starting_lives = 0);
+        assert_rejects("respawn_clear_radius", |c| c.respawn_clear_radius = -1.0);
+        assert_rejects("respawn_clear_radius", |c| {
+            c.respawn_clear_radius = f64::NAN
+        });
+    }
+
+    #[test]
+    fn rejects_a_dash_that_is_ready_before_it_ends() {
+        assert_rejects("dash_cooldown_ms", |c| {
+            c.dash_duration_ms = 300;
+            c.dash_cooldown_ms = 299;
+        });
+    }
+
+    #[test]
+    fn rejects_input_tuning_out_of_range() {
+        assert_rejects("player_friction", |c| c.player_friction = 0.0);
+        assert_rejects("player_friction", |c| c.player_friction = 1.5);
+        assert_rejects("analog_dead_zone", |c| c.analog_dead_zone = -0.1);
+        assert_rejects("analog_dead_zone", |c| c.analog_dead_zone = 1.0);
+    }
+
+    #[test]
+    fn rejects_spawn_areas_that_would_be_empty() {
+        assert_rejects("enemy_buffer_frac", |c| c.enemy_buffer_frac = 0.0);
+        assert_rejects("enemy_buffer_frac", |c| c.enemy_buffer_frac = 0.6);
+        assert_rejects("gate_buffer", |c| c.gate_buffer = -1.0);
+        assert_rejects("gate_buffer", |c| c.gate_buffer = c.window_height / 2.0);
+        assert_rejects("circle_radius", |c| c.circle_radius = c.window_height);
+    }
+}
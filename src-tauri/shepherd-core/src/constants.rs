@@ -0,0 +1,85 @@
+use crate::config::GameConfig;
+
+use serde::Serialize;
+
+pub const WINDOW_WIDTH: f64 = 1200.0;
+pub const WINDOW_HEIGHT: f64 = 800.0;
+
+pub const PLAYER_SPEED: f64 = 2.5;
+pub const ENEMY_SPEED: f64 = 1.75;
+pub const PLAYER_ACCELERATION: f64 = 0.25; // per tick, only with momentum
+pub const PLAYER_FRICTION: f64 = 0.1; // share of speed lost per tick while coasting
+pub const DASH_SPEED: f64 = 4.0 * PLAYER_SPEED;
+pub const DASH_DURATION_MS: u64 = 120;
+pub const DASH_COOLDOWN_MS: u64 = 1500; // from the start of one dash to the next
+pub const DASH_INVULNERABLE: bool = true;
+pub const ANALOG_DEAD_ZONE: f64 = 0.15; // fraction of full stick travel
+
+// One life and no extra ones plays like the original, where any hit ends the run
+pub const STARTING_LIVES: u32 = 1;
+pub const EXTRA_LIFE_SCORE: u64 = 0; // a life every this many points; 0 for none
+pub const RESPAWN_INVULNERABLE_MS: u64 = 2000;
+pub const RESPAWN_CLEAR_RADIUS: f64 = 200.0;
+
+pub const TICK_CYCLE_MS: u64 = 8;
+pub const PUBLISH_RATE_HZ: f64 = 60.0;
+pub const MAX_CATCH_UP_TICKS: u32 = 5;
+pub const ENEMY_SPAWN_INTERVAL: u64 = 5000;
+pub const GATE_SPAWN_INTERVAL: u64 = 7500;
+pub const GATE_ARM_MS: u64 = 5000;
+pub const COUNTDOWN_MS: u64 = 3000;
+pub const RESUME_ON_FOCUS: bool = false;
+
+pub const CIRCLE_RADIUS: f64 = 15.0;
+pub const DIAMOND_RADIUS: f64 = 25.0;
+pub const TRIANGLE_RADIUS: f64 = 75.0;
+pub const SQUARE_RADIUS: f64 = 5.0;
+
+pub const EXPLOSION_RADIUS: f64 = 150.0;
+pub const GATE_BUFFER: f64 = 25.0;
+pub const ENEMY_BUFFER_FRAC: f64 = 0.25;
+
+pub const MULTIPLIER_ATTRACT_MIN: f64 = 75.0;
+pub const MULTIPLIER_SPEED: f64 = 0.9 * PLAYER_SPEED;
+pub const MULTIPLIER_LIFETIME_MS: u64 = 5000;
+
+pub const HIGH_SCORE_TABLE_SIZE: usize = 10;
+
+// Counted in published frames, so about every two seconds at the default publish rate
+pub const KEYFRAME_INTERVAL: u64 = 120;
+pub const FRAME_POSITION_DECIMALS: u32 = 1;
+
+// Analog inputs are rounded before they are applied, so they survive a replay's JSON exactly
+pub const ANALOG_INPUT_DECIMALS: u32 = 3;
+
+// Roughly a gate's width, so most collision queries only touch a few cells
+pub const BROADPHASE_CELL_SIZE: f64 = 150.0;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct GameConstants {
+    window_width: f64,
+    window_height: f64,
+    circle_radius: f64,
+    diamond_radius: f64,
+    triangle_radius: f64,
+    square_radius: f64,
+    explosion_radius: f64,
+    publish_rate_hz: f64, // the frontend interpolates over one publish interval
+    tick_cycle_ms: u64,
+}
+
+impl GameConstants {
+    pub fn new(config: &GameConfig) -> Self {
+        Self {
+            window_width: config.window_width,
+            window_height: config.window_height,
+            circle_radius: config.circle_radius,
+            diamond_radius: config.diamond_radius,
+            triangle_radius: config.triangle_radius,
+            square_radius: config.square_radius,
+            explosion_radius: config.explosion_radius,
+            publish_rate_hz: config.publish_rate_hz,
+            tick_cycle_ms: config.tick_cycle_ms,
+        }
+    }
+}
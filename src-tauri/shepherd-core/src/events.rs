@@ -0,0 +1,66 @@
+use crate::game::DeathCause;
+use crate::game_objects::EntityId;
+use crate::phase::GamePhase;
+
+use serde::Serialize;
+
+// Everything notable that happens during a tick, in the order it happened. `Game` collects these
+// until they are drained, so several ticks' worth can go out together.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum GameEvent {
+    WaveStarted {
+        wave: usize,
+        enemies: usize,
+    },
+    EnemySpawned {
+        id: EntityId,
+        position: (f64, f64),
+    },
+    GateSpawned {
+        id: EntityId,
+        position: (f64, f64),
+    },
+    GateDetonated {
+        id: EntityId,
+        position: (f64, f64),
+    },
+    EnemyDestroyed {
+        id: EntityId,
+        position: (f64, f64),
+    },
+    MultiplierDropped {
+        id: EntityId,
+        position: (f64, f64),
+    },
+    MultiplierCollected {
+        id: EntityId,
+        multiplier: u64, // after collecting this one
+    },
+    MultiplierExpired {
+        id: EntityId,
+    },
+    // A hit with lives to spare; the last one is `PlayerDied` instead
+    LifeLost {
+        cause: DeathCause,
+        by: EntityId,
+        position: (f64, f64),
+        lives: u32, // left afterwards
+    },
+    EnemyCleared {
+        id: EntityId,
+        position: (f64, f64),
+    },
+    ExtraLife {
+        lives: u32,
+    },
+    PlayerDied {
+        cause: DeathCause,
+        by: EntityId,
+        position: (f64, f64),
+    },
+    // Unlike the rest, also happens between ticks, e.g. on pausing
+    PhaseChanged {
+        phase: GamePhase,
+    },
+}
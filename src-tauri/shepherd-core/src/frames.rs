@@ -0,0 +1,391 @@
+use crate::game_objects::{EntityId, EntitySprite};
+use crate::sprites::Sprite;
+use crate::traits::Shape;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::mem::discriminant;
+
+// One `update_sprites` payload. A keyframe lists every entity in `spawned`; other frames only
+// carry what changed since the previous one.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Frame {
+    pub seq: u64,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub keyframe: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub spawned: Vec<EntitySprite>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub moved: Vec<(EntityId, f64, f64)>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rotated: Vec<(EntityId, f64)>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub despawned: Vec<EntityId>,
+}
+
+// Tracks what the frontend was last sent so each frame only needs the differences
+#[derive(Clone, Debug)]
+pub struct FrameEncoder {
+    keyframe_interval: u64,
+    position_decimals: Option<u32>,
+    sent: BTreeMap<EntityId, Sprite>,
+    next_seq: u64,
+    keyframe_due: bool,
+}
+
+impl FrameEncoder {
+    // With `position_decimals` set, coordinates and rotations are rounded to that many decimal
+    // places, and only changes that survive the rounding are sent
+    pub fn new(keyframe_interval: u64, position_decimals: Option<u32>) -> Self {
+        Self {
+            keyframe_interval,
+            position_decimals,
+            sent: BTreeMap::new(),
+            next_seq: 0,
+            keyframe_due: true,
+        }
+    }
+
+    // Makes the next frame a keyframe, e.g. after a new listener has subscribed
+    pub fn request_keyframe(&mut self) {
+        self.keyframe_due = true;
+    }
+
+    pub fn encode(&mut self, sprites: &[EntitySprite]) -> Frame {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        let current = sprites
+            .iter()
+            .map(|entity| (entity.id, self.quantize(&entity.sprite)))
+            .collect::<BTreeMap<EntityId, Sprite>>();
+
+        let mut frame = Frame {
+            seq,
+            ..Frame::default()
+        };
+
+        if self.keyframe_due || seq.is_multiple_of(self.keyframe_interval) {
+            frame.keyframe = true;
+            frame.spawned = to_entity_sprites(&current);
+        } else {
+            for (&id, sprite) in &current {
+                match self.sent.get(&id) {
+                    // Ids restart with each game, so the same id may now be a different shape
+                    Some(previous) if discriminant(previous) == discriminant(sprite) => {
+                        if previous.get_coords() != sprite.get_coords() {
+                            let (x, y) = sprite.get_coords();
+                            frame.moved.push((id, x, y));
+                        }
+                        if let (Sprite::Triangle(_, _, before), Sprite::Triangle(_, _, after)) =
+                            (previous, sprite)
+                        {
+                            if before != after {
+                                frame.rotated.push((id, *after));
+                            }
+                        }
+                    }
+                    _ => frame.spawned.push(EntitySprite {
+                        id,
+                        sprite: sprite.clone(),
+                    }),
+                }
+            }
+
+            frame.despawned = self
+                .sent
+                .keys()
+                .filter(|id| !current.contains_key(id))
+                .copied()
+                .collect();
+        }
+
+        self.sent = current;
+        self.keyframe_due = false;
+        frame
+    }
+
+    fn quantize(&self, sprite: &Sprite) -> Sprite {
+        let Some(decimals) = self.position_decimals else {
+            return sprite.clone();
+        };
+
+        let scale = 10f64.powi(decimals as i32);
+        let round = |value: f64| (value * scale).round() / scale;
+        match sprite {
+            Sprite::Triangle(x, y, rotation) => {
+                Sprite::Triangle(round(*x), round(*y), round(*rotation))
+            }
+            Sprite::Circle(x, y) => Sprite::Circle(round(*x), round(*y)),
+            Sprite::Diamond(x, y) => Sprite::Diamond(round(*x), round(*y)),
+            Sprite::Square(x, y) => Sprite::Square(round(*x), round(*y)),
+            Sprite::Point(x, y) => Sprite::Point(round(*x), round(*y)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FrameError {
+    MissingKeyframe,
+    OutOfOrder { expected: u64, got: u64 },
+    UnknownEntity(EntityId),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::MissingKeyframe => write!(f, "delta frame received before any keyframe"),
+            FrameError::OutOfOrder { expected, got } => {
+                write!(f, "expected frame {}, got {}", expected, got)
+            }
+            FrameError::UnknownEntity(id) => write!(f, "delta for unknown entity {}", id.0),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+// Rebuilds the sprite list from a frame stream, as the frontend does
+#[derive(Clone, Debug, Default)]
+pub struct FrameDecoder {
+    sprites: BTreeMap<EntityId, Sprite>,
+    last_seq: Option<u64>,
+}
+
+impl FrameDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // A failed frame leaves the decoder waiting for the next keyframe
+    pub fn apply(&mut self, frame: &Frame) -> Result<(), FrameError> {
+        if frame.keyframe {
+            self.sprites.clear();
+        } else {
+            let expected = self.last_seq.ok_or(FrameError::MissingKeyframe)? + 1;
+            if frame.seq != expected {
+                self.last_seq = None;
+                return Err(FrameError::OutOfOrder {
+                    expected,
+                    got: frame.seq,
+                });
+            }
+        }
+
+        if let Err(e) = self.apply_changes(frame) {
+            self.last_seq = None;
+            return Err(e);
+        }
+
+        self.last_seq = Some(frame.seq);
+        Ok(())
+    }
+
+    fn apply_changes(&mut self, frame: &Frame) -> Result<(), FrameError> {
+        for id in &frame.despawned {
+            self.sprites.remove(id);
+        }
+
+        for entity in &frame.spawned {
+            self.sprites.insert(entity.id, entity.sprite.clone());
+        }
+
+        for &(id, new_x, new_y) in &frame.moved {
+            match self.sprites.get_mut(&id) {
+                Some(
+                    Sprite::Triangle(x, y, _)
+                    | Sprite::Circle(x, y)
+                    | Sprite::Diamond(x, y)
+                    | Sprite::Square(x, y)
+                    | Sprite::Point(x, y),
+                ) => {
+                    *x = new_x;
+                    *y = new_y;
+                }
+                None => return Err(FrameError::UnknownEntity(id)),
+            }
+        }
+
+        for &(id, new_rotation) in &frame.rotated {
+            match self.sprites.get_mut(&id) {
+                Some(Sprite::Triangle(_, _, rotation)) => *rotation = new_rotation,
+                _ => return Err(FrameError::UnknownEntity(id)),
+            }
+        }
+
+        Ok(())
+    }
+
+    // In id order, which is the order `Game::get_sprites` lists them in
+    pub fn sprites(&self) -> Vec<EntitySprite> {
+        to_entity_sprites(&self.sprites)
+    }
+}
+
+fn to_entity_sprites(sprites: &BTreeMap<EntityId, Sprite>) -> Vec<EntitySprite> {
+    sprites
+        .iter()
+        .map(|(&id, sprite)| EntitySprite {
+            id,
+            sprite: sprite.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::KEYFRAME_INTERVAL;
+    use crate::game::Game;
+    use crate::phase::GamePhase;
+
+    fn entity(id: u64, sprite: Sprite) -> EntitySprite {
+        EntitySprite {
+            id: EntityId(id),
+            sprite,
+        }
+    }
+
+    #[test]
+    fn decoded_frames_match_the_game() {
+        let mut game = Game::with_seed(7);
+        game.start().unwrap();
+        game.skip_countdown();
+
+        let mut encoder = FrameEncoder::new(KEYFRAME_INTERVAL, None);
+        let mut decoder = FrameDecoder::new();
+        let (mut despawns, mut restarts) = (0, 0);
+
+        for tick in 0..3000u64 {
+            // Circle around so gates get run through and enemies come and go
+            let angle = tick as f64 / 60.0;
+            game.set_stick(angle.cos(), angle.sin());
+            game.tick();
+
+            // A new game starts its ids over with whatever shapes it spawns
+            if game.phase() == GamePhase::GameOver {
+                game.restart().unwrap();
+                game.skip_countdown();
+                restarts += 1;
+            }
+
+            let frame = encoder.encode(&game.get_sprites());
+            assert_eq!(frame.seq, tick);
+            assert_eq!(frame.keyframe, tick % KEYFRAME_INTERVAL == 0);
+            despawns += frame.despawned.len();
+
+            decoder.apply(&frame).unwrap();
+            assert_eq!(decoder.sprites(), game.get_sprites(), "tick {}", tick);
+        }
+
+        assert!(despawns > 0 && restarts > 0);
+    }
+
+    #[test]
+    fn keyframes_come_every_interval_and_on_request() {
+        let sprites = [entity(0, Sprite::Circle(1.0, 2.0))];
+        let mut encoder = FrameEncoder::new(3, None);
+
+        let keyframes: Vec<bool> = (0..7).map(|_| encoder.encode(&sprites).keyframe).collect();
+        assert_eq!(keyframes, [true, false, false, true, false, false, true]);
+
+        // Nothing changed, so there is nothing to send
+        let frame = encoder.encode(&sprites);
+        assert_eq!(
+            frame,
+            Frame {
+                seq: 7,
+                ..Frame::default()
+            }
+        );
+
+        encoder.request_keyframe();
+        let frame = encoder.encode(&sprites);
+        assert!(frame.keyframe);
+        assert_eq!(frame.spawned, sprites);
+    }
+
+    #[test]
+    fn despawns_and_reused_ids() {
+        let mut encoder = FrameEncoder::new(100, None);
+        let mut decoder = FrameDecoder::new();
+
+        let first = [
+            entity(0, Sprite::Circle(1.0, 1.0)),
+            entity(1, Sprite::Diamond(5.0, 5.0)),
+        ];
+        decoder.apply(&encoder.encode(&first)).unwrap();
+
+        let moved = [entity(0, Sprite::Circle(2.0, 1.0))];
+        let frame = encoder.encode(&moved);
+        assert_eq!(frame.moved, [(EntityId(0), 2.0, 1.0)]);
+        assert_eq!(frame.despawned, [EntityId(1)]);
+        decoder.apply(&frame).unwrap();
+        assert_eq!(decoder.sprites(), moved);
+
+        // Same id as the diamond, but a new game has made it a triangle
+        let reused = [
+            entity(0, Sprite::Circle(2.0, 1.0)),
+            entity(1, Sprite::Triangle(5.0, 5.0, 90.0)),
+        ];
+        let frame = encoder.encode(&reused);
+        assert_eq!(frame.spawned, reused[1..]);
+        assert!(frame.moved.is_empty() && frame.rotated.is_empty());
+        decoder.apply(&frame).unwrap();
+        assert_eq!(decoder.sprites(), reused);
+
+        // An id that comes back as a different shape between frames is respawned, not moved
+        let reshaped = [
+            entity(0, Sprite::Square(2.0, 1.0)),
+            entity(1, Sprite::Triangle(6.0, 5.0, 45.0)),
+        ];
+        let frame = encoder.encode(&reshaped);
+        assert_eq!(frame.spawned, reshaped[..1]);
+        assert_eq!(frame.moved, [(EntityId(1), 6.0, 5.0)]);
+        assert_eq!(frame.rotated, [(EntityId(1), 45.0)]);
+        decoder.apply(&frame).unwrap();
+        assert_eq!(decoder.sprites(), reshaped);
+    }
+
+    #[test]
+    fn decoder_waits_for_a_keyframe_after_an_error() {
+        let mut encoder = FrameEncoder::new(4, None);
+        let frames: Vec<Frame> = (0..8)
+            .map(|i| encoder.encode(&[entity(0, Sprite::Circle(i as f64, 0.0))]))
+            .collect();
+
+        let mut decoder = FrameDecoder::new();
+        assert_eq!(decoder.apply(&frames[1]), Err(FrameError::MissingKeyframe));
+
+        decoder.apply(&frames[0]).unwrap();
+        assert_eq!(
+            decoder.apply(&frames[2]),
+            Err(FrameError::OutOfOrder {
+                expected: 1,
+                got: 2
+            })
+        );
+
+        // Even the frame that was skipped no longer applies until the next keyframe
+        assert_eq!(decoder.apply(&frames[1]), Err(FrameError::MissingKeyframe));
+        assert_eq!(decoder.apply(&frames[3]), Err(FrameError::MissingKeyframe));
+
+        decoder.apply(&frames[4]).unwrap();
+        decoder.apply(&frames[5]).unwrap();
+        assert_eq!(decoder.sprites(), [entity(0, Sprite::Circle(5.0, 0.0))]);
+
+        // A delta for something the decoder never saw is an error too
+        let unknown = Frame {
+            seq: 6,
+            moved: vec![(EntityId(9), 1.0, 1.0)],
+            ..Frame::default()
+        };
+        assert_eq!(
+            decoder.apply(&unknown),
+            Err(FrameError::UnknownEntity(EntityId(9)))
+        );
+        assert_eq!(decoder.apply(&frames[7]), Err(FrameError::MissingKeyframe));
+    }
+}
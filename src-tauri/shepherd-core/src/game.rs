@@ -0,0 +1,994 @@
+use crate::broadphase::SpatialHash;
+use crate::collision::{check_corner_collision, check_edge_collision};
+use crate::config::GameConfig;
+use crate::constants::BROADPHASE_CELL_SIZE;
+use crate::events::GameEvent;
+use crate::game_objects::{EntityId, EntitySprite, GameObject};
+use crate::input::{quantize, Action, Controls};
+use crate::phase::{GamePhase, PhaseError};
+use crate::replay::{InputEvent, RecordedInput, Replay};
+use crate::sprites::Sprite;
+use crate::traits::{Entity, Shape};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+
+// Keep random seeds small enough to survive a round trip through JS numbers
+pub fn random_seed() -> u64 {
+    rand::random::<u32>().into()
+}
+
+#[derive(Clone, Debug)]
+pub struct GameState {
+    pub controls: Controls,
+    pub player: GameObject,
+}
+
+impl GameState {
+    pub fn new(config: &GameConfig) -> Self {
+        Self {
+            controls: Controls::default(),
+            player: GameObject::new_player(config),
+        }
+    }
+}
+
+// What entities can see of the rest of the game while they update
+pub struct TickContext<'a> {
+    pub player_position: (f64, f64),
+    pub controls: &'a Controls,
+    pub config: &'a GameConfig,
+    pub sim_time_ms: u64,
+}
+
+// For the HUD; times are simulation time
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct DashStatus {
+    pub active: bool,
+    pub invulnerable: bool,
+    pub cooldown_remaining_ms: u64, // 0 once another dash is available
+    pub cooldown_ms: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum DeathCause {
+    Enemy,
+    GateCorner,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct GameOverInfo {
+    pub cause: DeathCause,
+    pub entity: EntityId,
+    pub position: (f64, f64), // of the object that ended the run
+    pub final_score: u64,
+    pub peak_multiplier: u64,
+    pub survival_time_ms: u64,
+    pub enemies_destroyed: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct Game {
+    game_state: GameState,
+    game_objects: Vec<GameObject>,
+    broadphase: SpatialHash, // indexes `game_objects` by position during collision checks
+    pub score: u64,
+    pub multiplier: u64,
+    lives: u32,
+    next_extra_life_at: u64, // score; unused without extra lives
+    invulnerable_until: u64, // simulation time, after a respawn
+    events: Vec<GameEvent>,
+    phase: GamePhase,
+    countdown_remaining_ms: u64,
+    spawn_count: usize,
+    next_entity_id: u64,
+    game_over_info: Option<GameOverInfo>,
+    peak_multiplier: u64,
+    enemies_destroyed: u64,
+    seed: u64,
+    seed_pinned: bool, // chosen by the player, so kept across restarts
+    rng: ChaCha8Rng,
+    tick_count: u64,
+    sim_time_ms: u64, // summed per tick, so a reloaded tick length doesn't move the past
+    last_enemy_spawn_ms: u64,
+    last_gate_spawn_ms: u64,
+    replay: Replay,
+    config: GameConfig,
+}
+
+impl Game {
+    pub fn new() -> Self {
+        Self::with_seed(random_seed())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_config(seed, GameConfig::default())
+    }
+
+    pub fn with_config(seed: u64, config: GameConfig) -> Self {
+        Self {
+            game_state: GameState::new(&config),
+            game_objects: Vec::new(),
+            broadphase: SpatialHash::new(BROADPHASE_CELL_SIZE),
+            score: 0,
+            multiplier: 1,
+            lives: config.starting_lives,
+            next_extra_life_at: config.extra_life_score,
+            invulnerable_until: 0,
+            events: Vec::new(),
+            phase: GamePhase::Title,
+            countdown_remaining_ms: 0,
+            spawn_count: 1,
+            next_entity_id: EntityId::PLAYER.0 + 1,
+            game_over_info: None,
+            peak_multiplier: 1,
+            enemies_destroyed: 0,
+            seed,
+            seed_pinned: false,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            tick_count: 0,
+            sim_time_ms: 0,
+            last_enemy_spawn_ms: 0,
+            last_gate_spawn_ms: 0,
+            replay: Replay::new(seed, config.clone()),
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    pub fn phase(&self) -> GamePhase {
+        self.phase
+    }
+
+    // Set once, on the tick the run ends
+    pub fn game_over_info(&self) -> Option<&GameOverInfo> {
+        self.game_over_info.as_ref()
+    }
+
+    pub fn peak_multiplier(&self) -> u64 {
+        self.peak_multiplier
+    }
+
+    pub fn enemies_destroyed(&self) -> u64 {
+        self.enemies_destroyed
+    }
+
+    pub fn dash_status(&self) -> DashStatus {
+        let now = self.sim_time_ms();
+        let dash = self.game_state.player.get_data().dash;
+        let active = dash.is_some_and(|dash| now < dash.ends_at);
+
+        DashStatus {
+            active,
+            invulnerable: active && self.config.dash_invulnerable,
+            cooldown_remaining_ms: dash.map_or(0, |dash| dash.ready_at.saturating_sub(now)),
+            cooldown_ms: self.config.dash_cooldown_ms,
+        }
+    }
+
+    pub fn lives(&self) -> u32 {
+        self.lives
+    }
+
+    // Either just respawned or dashing through things
+    pub fn player_invulnerable(&self) -> bool {
+        self.sim_time_ms() < self.invulnerable_until || self.dash_status().invulnerable
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn tick_count(&self) -> u64 {
+        self.tick_count
+    }
+
+    // Simulation time only advances with ticks, so it stands still while paused
+    pub fn sim_time_ms(&self) -> u64 {
+        self.sim_time_ms
+    }
+
+    // Title -> Countdown
+    pub fn start(&mut self) -> Result<(), PhaseError> {
+        self.begin_countdown()
+    }
+
+    // GameOver -> Countdown, into a fresh run on a new seed unless this one was pinned
+    pub fn restart(&mut self) -> Result<(), PhaseError> {
+        self.check_restart()?;
+        self.reset_game(self.next_seed());
+        self.begin_countdown()
+    }
+
+    // GameOver -> Countdown, playing the same seed again
+    pub fn retry(&mut self) -> Result<(), PhaseError> {
+        self.check_restart()?;
+        self.reset_game(self.seed);
+        self.begin_countdown()
+    }
+
+    // Paused or GameOver -> Title, abandoning the run
+    pub fn return_to_title(&mut self) -> Result<(), PhaseError> {
+        self.set_phase(GamePhase::Title)?;
+        self.reset_game(self.next_seed());
+        Ok(())
+    }
+
+    // Paused -> Countdown is only for resuming, so a run in progress can't be thrown away here
+    pub fn check_restart(&self) -> Result<(), PhaseError> {
+        if self.phase != GamePhase::GameOver {
+            return Err(PhaseError {
+                from: self.phase,
+                to: GamePhase::Countdown,
+            });
+        }
+        Ok(())
+    }
+
+    // Keeps the current seed for every later run, as when the player picked it
+    pub fn pin_seed(&mut self) {
+        self.seed_pinned = true;
+    }
+
+    fn next_seed(&self) -> u64 {
+        if self.seed_pinned {
+            self.seed
+        } else {
+            random_seed()
+        }
+    }
+
+    // Countdown or Playing -> Paused
+    pub fn pause(&mut self) -> Result<(), PhaseError> {
+        self.apply_input(InputEvent::Pause)
+    }
+
+    // Paused -> Playing
+    pub fn resume(&mut self) -> Result<(), PhaseError> {
+        self.apply_input(InputEvent::Resume)
+    }
+
+    // Paused -> Countdown -> Playing, so the player has a moment to find their bearings
+    pub fn resume_after_countdown(&mut self) -> Result<(), PhaseError> {
+        self.begin_countdown()?;
+
+        // The countdown never touches the simulation, so replays just see the resume
+        self.record(InputEvent::Resume);
+        Ok(())
+    }
+
+    // Countdown -> Playing without waiting, for runs nobody is watching
+    pub fn skip_countdown(&mut self) {
+        if self.phase == GamePhase::Countdown {
+            self.enter_phase(GamePhase::Playing);
+        }
+    }
+
+    fn begin_countdown(&mut self) -> Result<(), PhaseError> {
+        self.set_phase(GamePhase::Countdown)?;
+        self.countdown_remaining_ms = self.config.countdown_ms;
+        if self.countdown_remaining_ms == 0 {
+            self.skip_countdown();
+        }
+        Ok(())
+    }
+
+    fn check_transition(&self, next: GamePhase) -> Result<(), PhaseError> {
+        if !self.phase.can_transition_to(next) {
+            return Err(PhaseError {
+                from: self.phase,
+                to: next,
+            });
+        }
+        Ok(())
+    }
+
+    fn set_phase(&mut self, next: GamePhase) -> Result<(), PhaseError> {
+        self.check_transition(next)?;
+        self.enter_phase(next);
+        Ok(())
+    }
+
+    // Every phase change goes through here, so none can happen without an event
+    fn enter_phase(&mut self, phase: GamePhase) {
+        self.phase = phase;
+        self.events.push(GameEvent::PhaseChanged { phase });
+    }
+
+    fn reset_game(&mut self, seed: u64) {
+        self.seed = seed;
+        self.game_state = GameState::new(&self.config);
+        self.score = 0;
+        self.multiplier = 1;
+        self.lives = self.config.starting_lives;
+        self.next_extra_life_at = self.config.extra_life_score;
+        self.invulnerable_until = 0;
+        self.spawn_count = 1;
+        self.next_entity_id = EntityId::PLAYER.0 + 1;
+        self.game_objects.clear();
+        self.game_over_info = None;
+        self.peak_multiplier = 1;
+        self.enemies_destroyed = 0;
+        self.rng = ChaCha8Rng::seed_from_u64(self.seed);
+        self.tick_count = 0;
+        self.sim_time_ms = 0;
+        self.last_enemy_spawn_ms = 0;
+        self.last_gate_spawn_ms = 0;
+        self.replay = Replay::new(self.seed, self.config.clone());
+    }
+
+    // Inputs received since the last reset, enough to reproduce the run from its seed
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    // Rejected inputs are not recorded
+    pub fn apply_input(&mut self, event: InputEvent) -> Result<(), PhaseError> {
+        let event = match event {
+            InputEvent::Stick(x, y) => InputEvent::Stick(quantize(x), quantize(y)),
+            InputEvent::Target(target) => {
+                InputEvent::Target(target.map(|(x, y)| (quantize(x), quantize(y))))
+            }
+            event => event,
+        };
+
+        let controls = &mut self.game_state.controls;
+        match &event {
+            InputEvent::Press(action) => {
+                controls.actions.insert(*action);
+            }
+            InputEvent::Release(action) => {
+                controls.actions.remove(action);
+            }
+            InputEvent::Stick(x, y) => controls.stick = (*x, *y),
+            InputEvent::Target(target) => controls.target = *target,
+            InputEvent::Dash => controls.dash_requested = true,
+            InputEvent::Pause => self.set_phase(GamePhase::Paused)?,
+            InputEvent::Resume => self.set_phase(GamePhase::Playing)?,
+            InputEvent::Config(config) => self.config = (**config).clone(),
+        }
+
+        self.record(event);
+        Ok(())
+    }
+
+    fn record(&mut self, event: InputEvent) {
+        self.replay.inputs.push(RecordedInput {
+            tick: self.tick_count,
+            event,
+        });
+    }
+
+    // Lets go of every held action and analog input, for when their releases may never arrive
+    pub fn release_all(&mut self) {
+        let controls = &self.game_state.controls;
+        let mut actions = controls.actions.iter().copied().collect::<Vec<Action>>();
+        actions.sort(); // recorded in a stable order
+        let stick_moved = controls.stick != (0.0, 0.0);
+        let targeting = controls.target.is_some();
+
+        for action in actions {
+            self.release(action);
+        }
+        if stick_moved {
+            self.set_stick(0.0, 0.0);
+        }
+        if targeting {
+            self.set_target(None);
+        }
+    }
+
+    // Takes effect from the next tick; recorded so replays see the same tuning
+    pub fn set_config(&mut self, config: GameConfig) {
+        self.apply_input(InputEvent::Config(Box::new(config)))
+            .expect("config changes are never rejected");
+    }
+
+    fn boom(&mut self, bx: f64, by: f64) {
+        let boom_strength = 500.0; // Adjust this constant based on desired effect
+        let epsilon = 1.0;
+
+        let diamonds_in_range = self
+            .broadphase
+            .query((bx, by), self.config.explosion_radius)
+            .into_iter()
+            .filter(|&index| match &self.game_objects[index] {
+                GameObject::Enemy(Sprite::Diamond(ex, ey), _) => {
+                    ((*ex - bx).powi(2) + (*ey - by).powi(2)).sqrt() < self.config.explosion_radius
+                }
+                _ => false,
+            })
+            .collect::<Vec<usize>>();
+        let boomed_diamonds = self.take_objects(&diamonds_in_range);
+
+        for diamond_object in boomed_diamonds {
+            let (dx, dy) = diamond_object.get_sprite().get_coords();
+            let distance = ((dx - bx).powi(2) + (dy - by).powi(2)).sqrt();
+
+            let velocity_magnitude = boom_strength / (distance + epsilon);
+            let direction_x = (dx - bx) / distance;
+            let direction_y = (dy - by) / distance;
+
+            let velocity_x = direction_x * velocity_magnitude;
+            let velocity_y = direction_y * velocity_magnitude;
+
+            // increment score and create a multiplier
+            self.score += self.multiplier;
+            self.enemies_destroyed += 1;
+            self.events.push(GameEvent::EnemyDestroyed {
+                id: diamond_object.id(),
+                position: (dx, dy),
+            });
+
+            let id = self.next_entity_id();
+            self.game_objects.push(GameObject::new_multiplier(
+                id,
+                &(dx, dy),
+                &(velocity_x, velocity_y),
+                self.sim_time_ms(),
+            ));
+            self.events.push(GameEvent::MultiplierDropped {
+                id,
+                position: (dx, dy),
+            });
+        }
+
+        self.award_extra_lives();
+        self.rebuild_broadphase();
+    }
+
+    // Everything that happened since the last call, oldest first
+    pub fn drain_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn get_sprites(&self) -> Vec<EntitySprite> {
+        let mut sprites = vec![self.game_state.player.to_entity_sprite()];
+        for game_object in &self.game_objects {
+            sprites.push(game_object.to_entity_sprite())
+        }
+        sprites
+    }
+
+    fn next_entity_id(&mut self) -> EntityId {
+        let id = EntityId(self.next_entity_id);
+        self.next_entity_id += 1;
+        id
+    }
+
+    // Counts down or advances the simulation by one step, depending on the phase
+    pub fn tick(&mut self) {
+        match self.phase {
+            GamePhase::Countdown => {
+                let tick_cycle_ms = self.config.tick_cycle_ms;
+                self.countdown_remaining_ms =
+                    self.countdown_remaining_ms.saturating_sub(tick_cycle_ms);
+                if self.countdown_remaining_ms == 0 {
+                    self.enter_phase(GamePhase::Playing);
+                }
+                return;
+            }
+            GamePhase::Playing => {}
+            _ => return,
+        }
+
+        self.tick_count += 1;
+        self.sim_time_ms += self.config.tick_cycle_ms;
+        let now = self.sim_time_ms();
+
+        if now.saturating_sub(self.last_enemy_spawn_ms) > self.config.enemy_spawn_interval {
+            self.spawn_enemy();
+            self.last_enemy_spawn_ms = now;
+        }
+
+        if now.saturating_sub(self.last_gate_spawn_ms) > self.config.gate_spawn_interval {
+            self.spawn_gate();
+            self.last_gate_spawn_ms = now;
+        }
+
+        // The player moves first so everything else reacts to where it is now
+        let mut ctx = TickContext {
+            player_position: self.game_state.player.get_sprite().get_coords(),
+            controls: &self.game_state.controls,
+            config: &self.config,
+            sim_time_ms: now,
+        };
+        self.game_state.player.update(&ctx);
+
+        ctx.player_position = self.game_state.player.get_sprite().get_coords();
+        for sprite_data in &mut self.game_objects {
+            sprite_data.update(&ctx);
+        }
+        self.game_state.controls.dash_requested = false;
+
+        self.check_collisions();
+        self.cull();
+    }
+
+    pub fn spawn_enemy(&mut self) {
+        let width = self.config.window_width;
+        let height = self.config.window_height;
+        let horizontal_buffer = self.config.enemy_buffer_frac * width;
+        let vertical_buffer = self.config.enemy_buffer_frac * height;
+        let (x_min, x_max, y_min, y_max) = match self.rng.gen_range(0..4) {
+            0 => (0.0, horizontal_buffer, 0.0, vertical_buffer),
+            1 => (width - horizontal_buffer, width, 0.0, vertical_buffer),
+            2 => (
+                width - horizontal_buffer,
+                width,
+                height - vertical_buffer,
+                height,
+            ),
+            _ => (0.0, horizontal_buffer, height - vertical_buffer, height),
+        };
+
+        self.events.push(GameEvent::WaveStarted {
+            wave: self.spawn_count,
+            enemies: self.spawn_count,
+        });
+        for _ in 0..self.spawn_count {
+            let x = self.rng.gen_range(x_min..x_max);
+            let y = self.rng.gen_range(y_min..y_max);
+            let id = self.next_entity_id();
+            self.game_objects.push(GameObject::new_enemy(id, &(x, y)));
+            self.events.push(GameEvent::EnemySpawned {
+                id,
+                position: (x, y),
+            });
+        }
+
+        self.spawn_count += 1;
+    }
+
+    pub fn spawn_gate(&mut self) {
+        let gate_buffer = self.config.gate_buffer;
+        let gx = self
+            .rng
+            .gen_range(gate_buffer..(self.config.window_width - gate_buffer));
+        let gy = self
+            .rng
+            .gen_range(gate_buffer..(self.config.window_height - gate_buffer));
+        let gr = self.rng.gen_range(0.0..360.0);
+        let gate_spin = self.rng.gen_range(-1.0..1.0);
+        let id = self.next_entity_id();
+        self.game_objects.push(GameObject::new_gate(
+            id,
+            &(gx, gy),
+            gr,
+            gate_spin,
+            self.sim_time_ms(),
+        ));
+        self.events.push(GameEvent::GateSpawned {
+            id,
+            position: (gx, gy),
+        });
+    }
+
+    fn check_collisions(&mut self) {
+        let (cx, cy) = self.game_state.player.get_sprite().get_coords();
+        let now = self.sim_time_ms();
+        let invulnerable = self.player_invulnerable();
+
+        // Nothing further than these from the player can touch it
+        let triangle_reach = self.config.circle_radius + self.config.triangle_radius;
+        let diamond_reach = self.config.circle_radius + self.config.diamond_radius;
+        let square_reach = self.config.circle_radius + self.config.square_radius;
+
+        self.rebuild_broadphase();
+
+        let triangles_hit = self
+            .broadphase
+            .query((cx, cy), triangle_reach)
+            .into_iter()
+            .filter(|&index| match self.game_objects[index].get_sprite() {
+                sprite @ Sprite::Triangle(_, _, _) => {
+                    check_edge_collision(sprite, &self.game_state, &self.config)
+                }
+                _ => false,
+            })
+            .collect::<Vec<usize>>();
+        let triangles_to_boom = self.take_objects(&triangles_hit);
+
+        if triangles_to_boom.is_empty() {
+            let gate_hit = self
+                .broadphase
+                .query((cx, cy), triangle_reach)
+                .into_iter()
+                .find_map(|index| match &self.game_objects[index] {
+                    GameObject::Gate(sprite, data)
+                        if data.spawn_time.is_some_and(|spawn_time| {
+                            now.saturating_sub(spawn_time) > self.config.gate_arm_ms
+                        }) && check_corner_collision(sprite, &self.game_state, &self.config) =>
+                    {
+                        Some((data.id, sprite.get_coords()))
+                    }
+                    _ => None,
+                });
+
+            // check enemy-player collision (=> game over, you lose, good day sir!)
+            let enemy_hit = || {
+                self.broadphase
+                    .query((cx, cy), diamond_reach)
+                    .into_iter()
+                    .find_map(|index| match &self.game_objects[index] {
+                        GameObject::Enemy(sprite, data)
+                            if check_edge_collision(sprite, &self.game_state, &self.config) =>
+                        {
+                            Some((data.id, sprite.get_coords()))
+                        }
+                        _ => None,
+                    })
+            };
+
+            let death = gate_hit
+                .map(|hit| (DeathCause::GateCorner, hit))
+                .or_else(|| enemy_hit().map(|hit| (DeathCause::Enemy, hit)));
+            if let Some((cause, (entity, position))) = death.filter(|_| !invulnerable) {
+                self.lose_life(cause, entity, position);
+            }
+        } else {
+            for triangle in triangles_to_boom {
+                let (triangle_x, triangle_y) = triangle.get_sprite().get_coords();
+                self.events.push(GameEvent::GateDetonated {
+                    id: triangle.id(),
+                    position: (triangle_x, triangle_y),
+                });
+                self.boom(triangle_x, triangle_y);
+            }
+        }
+
+        // Now check multiplier collisions, from wherever a respawn may have just put the player
+        let (cx, cy) = self.game_state.player.get_sprite().get_coords();
+        let squares_hit = self
+            .broadphase
+            .query((cx, cy), square_reach)
+            .into_iter()
+            .filter(|&index| match &self.game_objects[index] {
+                GameObject::Multiplier(sprite, _) => {
+                    let (mx, my) = sprite.get_coords();
+                    let dx = cx - mx;
+                    let dy = cy - my;
+                    let distance = (dx * dx + dy * dy).sqrt();
+                    distance < square_reach
+                }
+                _ => false,
+            })
+            .collect::<Vec<usize>>();
+        let squares_to_consume = self.take_objects(&squares_hit);
+
+        for square in squares_to_consume {
+            self.multiplier += 1;
+            self.peak_multiplier = self.peak_multiplier.max(self.multiplier);
+            self.events.push(GameEvent::MultiplierCollected {
+                id: square.id(),
+                multiplier: self.multiplier,
+            });
+        }
+    }
+
+    fn rebuild_broadphase(&mut self) {
+        self.broadphase.clear();
+        for (index, game_object) in self.game_objects.iter().enumerate() {
+            self.broadphase
+                .insert(index, game_object.get_sprite().get_coords());
+        }
+    }
+
+    // Removes the objects at `indices` (ascending), keeping the rest in order. Indices shift, so
+    // the broadphase is rebuilt before anything queries it again.
+    fn take_objects(&mut self, indices: &[usize]) -> Vec<GameObject> {
+        if indices.is_empty() {
+            return Vec::new();
+        }
+
+        let mut indices = indices.iter().copied().peekable();
+        let mut index = 0;
+        let taken = self
+            .game_objects
+            .extract_if(.., |_| {
+                let take = indices.next_if_eq(&index).is_some();
+                index += 1;
+                take
+            })
+            .collect();
+
+        self.rebuild_broadphase();
+        taken
+    }
+
+    fn lose_life(&mut self, cause: DeathCause, entity: EntityId, position: (f64, f64)) {
+        // A game started with no lives at all ends on its first hit too
+        self.lives = self.lives.saturating_sub(1);
+        if self.lives == 0 {
+            self.end_game(cause, entity, position);
+            return;
+        }
+
+        self.events.push(GameEvent::LifeLost {
+            cause,
+            by: entity,
+            position,
+            lives: self.lives,
+        });
+        self.respawn();
+    }
+
+    // Back to the centre with a moment's grace, minus any enemies waiting at either end
+    fn respawn(&mut self) {
+        let died_at = self.game_state.player.get_sprite().get_coords();
+        self.game_state.player = GameObject::new_player(&self.config);
+        let respawn_at = self.game_state.player.get_sprite().get_coords();
+        self.invulnerable_until = self.sim_time_ms() + self.config.respawn_invulnerable_ms;
+
+        let radius = self.config.respawn_clear_radius;
+        let mut nearby = self.broadphase.query(died_at, radius);
+        nearby.extend(self.broadphase.query(respawn_at, radius));
+        nearby.sort_unstable();
+        nearby.dedup();
+
+        let within = |(x, y): (f64, f64), (px, py): (f64, f64)| {
+            ((x - px).powi(2) + (y - py).powi(2)).sqrt() < radius
+        };
+        let enemies = nearby
+            .into_iter()
+            .filter(|&index| match &self.game_objects[index] {
+                GameObject::Enemy(sprite, _) => {
+                    let coords = sprite.get_coords();
+                    within(coords, died_at) || within(coords, respawn_at)
+                }
+                _ => false,
+            })
+            .collect::<Vec<usize>>();
+
+        for enemy in self.take_objects(&enemies) {
+            self.events.push(GameEvent::EnemyCleared {
+                id: enemy.id(),
+                position: enemy.get_sprite().get_coords(),
+            });
+        }
+    }
+
+    // Extra lives for every `extra_life_score` points passed
+    fn award_extra_lives(&mut self) {
+        let every = self.config.extra_life_score;
+        if every == 0 {
+            return;
+        }
+
+        // The interval may have been turned on by a config reload since the run started
+        self.next_extra_life_at = self.next_extra_life_at.max(every);
+        while self.score >= self.next_extra_life_at {
+            self.lives += 1;
+            self.next_extra_life_at += every;
+            self.events.push(GameEvent::ExtraLife { lives: self.lives });
+        }
+    }
+
+    fn end_game(&mut self, cause: DeathCause, entity: EntityId, position: (f64, f64)) {
+        self.events.push(GameEvent::PlayerDied {
+            cause,
+            by: entity,
+            position,
+        });
+        self.enter_phase(GamePhase::GameOver);
+        self.game_over_info = Some(GameOverInfo {
+            cause,
+            entity,
+            position,
+            final_score: self.score,
+            peak_multiplier: self.peak_multiplier,
+            survival_time_ms: self.sim_time_ms(),
+            enemies_destroyed: self.enemies_destroyed,
+        });
+    }
+
+    fn cull(&mut self) {
+        let now = self.sim_time_ms();
+        let lifetime = self.config.multiplier_lifetime_ms;
+        self.game_objects.retain(|game_object| match game_object {
+            GameObject::Multiplier(_, data) => {
+                let alive = data
+                    .spawn_time
+                    .is_none_or(|spawn_time| now.saturating_sub(spawn_time) < lifetime);
+                if !alive {
+                    self.events
+                        .push(GameEvent::MultiplierExpired { id: data.id });
+                }
+                alive
+            }
+            _ => true,
+        });
+    }
+
+    pub fn press(&mut self, action: Action) {
+        self.apply_input(InputEvent::Press(action))
+            .expect("action inputs are never rejected");
+    }
+
+    pub fn release(&mut self, action: Action) {
+        self.apply_input(InputEvent::Release(action))
+            .expect("action inputs are never rejected");
+    }
+
+    // Dashes on the next tick, if the cooldown is over and the player has a direction to go in
+    pub fn dash(&mut self) {
+        self.apply_input(InputEvent::Dash)
+            .expect("action inputs are never rejected");
+    }
+
+    pub fn set_stick(&mut self, x: f64, y: f64) {
+        self.apply_input(InputEvent::Stick(x, y))
+            .expect("analog inputs are never rejected");
+    }
+
+    pub fn set_target(&mut self, target: Option<(f64, f64)>) {
+        self.apply_input(InputEvent::Target(target))
+            .expect("analog inputs are never rejected");
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use GamePhase::*;
+
+    // Plays the same run each time, draining every `drain_every` ticks
+    fn play(drain_every: u64) -> Vec<GameEvent> {
+        let mut game = Game::with_seed(7);
+        game.start().unwrap();
+        game.skip_countdown();
+
+        let mut events = Vec::new();
+        for tick in 1..=3000u64 {
+            let angle = tick as f64 / 60.0;
+            game.set_stick(angle.cos(), angle.sin());
+            game.tick();
+
+            if tick % drain_every == 0 || game.phase() == GamePhase::GameOver {
+                events.extend(game.drain_events());
+                assert!(game.drain_events().is_empty());
+            }
+            if game.phase() == GamePhase::GameOver {
+                break;
+            }
+        }
+
+        events
+    }
+
+    fn playing() -> Game {
+        let mut game = Game::with_seed(7);
+        game.start().unwrap();
+        game.skip_countdown();
+        for _ in 0..100 {
+            game.tick();
+        }
+        game
+    }
+
+    fn game_over() -> Game {
+        let mut game = playing();
+        while game.phase() != GamePhase::GameOver {
+            game.tick();
+        }
+        game
+    }
+
+    fn rejected(from: GamePhase, to: GamePhase) -> Result<(), PhaseError> {
+        Err(PhaseError { from, to })
+    }
+
+    #[test]
+    fn phase_entry_points_only_work_from_their_phases() {
+        let mut game = Game::with_seed(7);
+        assert_eq!(game.pause(), rejected(Title, Paused));
+        assert_eq!(game.return_to_title(), rejected(Title, Title));
+        game.start().unwrap();
+        assert_eq!(game.phase(), Countdown);
+        assert_eq!(game.start(), rejected(Countdown, Countdown));
+
+        let mut game = playing();
+        assert_eq!(game.resume(), rejected(Playing, Playing));
+        assert_eq!(game.return_to_title(), rejected(Playing, Title));
+        game.pause().unwrap();
+        assert_eq!(game.phase(), Paused);
+        game.resume().unwrap();
+        assert_eq!(game.phase(), Playing);
+        game.pause().unwrap();
+        game.resume_after_countdown().unwrap();
+        assert_eq!(game.phase(), Countdown);
+
+        let mut game = game_over();
+        assert_eq!(game.pause(), rejected(GameOver, Paused));
+        game.return_to_title().unwrap();
+        assert_eq!(game.phase(), Title);
+    }
+
+    #[test]
+    fn restart_and_retry_need_a_finished_run() {
+        let mut game = playing();
+        for phase in [Playing, Paused] {
+            if phase == Paused {
+                game.pause().unwrap();
+            }
+            assert_eq!(game.restart(), rejected(phase, Countdown));
+            assert_eq!(game.retry(), rejected(phase, Countdown));
+
+            // The run carries on untouched
+            assert_eq!(game.phase(), phase);
+            assert_eq!(game.tick_count(), 100);
+        }
+
+        let mut game = Game::with_seed(7);
+        assert_eq!(game.restart(), rejected(Title, Countdown));
+        game.start().unwrap();
+        assert_eq!(game.retry(), rejected(Countdown, Countdown));
+    }
+
+    #[test]
+    fn retry_keeps_the_seed_and_restart_only_when_pinned() {
+        let mut game = game_over();
+        game.retry().unwrap();
+        assert_eq!(
+            (game.phase(), game.seed(), game.tick_count()),
+            (Countdown, 7, 0)
+        );
+
+        let mut game = game_over();
+        game.pin_seed();
+        game.restart().unwrap();
+        assert_eq!(
+            (game.phase(), game.seed(), game.tick_count()),
+            (Countdown, 7, 0)
+        );
+
+        // Unpinned, a fresh seed could come up 7 by chance, but not twice running
+        let seeds: Vec<u64> = (0..2)
+            .map(|_| {
+                let mut game = game_over();
+                game.restart().unwrap();
+                game.seed()
+            })
+            .collect();
+        assert!(seeds.iter().any(|&seed| seed != 7));
+    }
+
+    #[test]
+    fn drained_events_are_the_same_however_often_they_are_drained() {
+        let every_tick = play(1);
+        assert_eq!(play(7), every_tick);
+        assert_eq!(play(u64::MAX), every_tick);
+
+        assert!(matches!(
+            every_tick[..3],
+            [
+                GameEvent::PhaseChanged {
+                    phase: GamePhase::Countdown
+                },
+                GameEvent::PhaseChanged {
+                    phase: GamePhase::Playing
+                },
+                GameEvent::WaveStarted { wave: 1, .. },
+            ]
+        ));
+        assert!(matches!(
+            every_tick[every_tick.len() - 2..],
+            [
+                GameEvent::PlayerDied { .. },
+                GameEvent::PhaseChanged {
+                    phase: GamePhase::GameOver
+                },
+            ]
+        ));
+    }
+}
@@ -0,0 +1,301 @@
+use crate::config::{GameConfig, Movement};
+use crate::game::TickContext;
+use crate::sprites::Sprite;
+use crate::traits::Entity;
+
+use serde::{Deserialize, Serialize};
+
+// Assigned at spawn and never reused within a run, so the frontend can follow an object
+// between frames
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub struct EntityId(pub u64);
+
+impl EntityId {
+    pub const PLAYER: EntityId = EntityId(0);
+}
+
+// What the frontend draws: `{"id": 3, "Diamond": [x, y]}`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EntitySprite {
+    pub id: EntityId,
+    #[serde(flatten)]
+    pub sprite: Sprite,
+}
+
+// The player's latest dash, in simulation time
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Dash {
+    pub direction: (f64, f64), // unit length
+    pub ends_at: u64,
+    pub ready_at: u64, // when the next dash can start
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct GameObjectData {
+    pub id: EntityId,
+    pub rotation_speed: Option<f64>,
+    pub velocity: Option<(f64, f64)>,
+    pub spawn_time: Option<u64>, // simulation time in ms
+    pub dash: Option<Dash>,
+    pub heading: Option<(f64, f64)>, // last way the player moved, for dashing from a standstill
+}
+
+impl GameObjectData {
+    fn new(id: EntityId) -> Self {
+        Self {
+            id,
+            rotation_speed: None,
+            velocity: None,
+            spawn_time: None,
+            dash: None,
+            heading: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum GameObject {
+    Player(Sprite, GameObjectData),
+    Gate(Sprite, GameObjectData),
+    Enemy(Sprite, GameObjectData),
+    Multiplier(Sprite, GameObjectData),
+}
+
+impl GameObject {
+    pub fn get_sprite(&self) -> &Sprite {
+        match self {
+            GameObject::Player(sprite, _) => sprite,
+            GameObject::Gate(sprite, _) => sprite,
+            GameObject::Enemy(sprite, _) => sprite,
+            GameObject::Multiplier(sprite, _) => sprite,
+        }
+    }
+
+    pub fn get_data(&self) -> &GameObjectData {
+        match self {
+            GameObject::Player(_, data) => data,
+            GameObject::Gate(_, data) => data,
+            GameObject::Enemy(_, data) => data,
+            GameObject::Multiplier(_, data) => data,
+        }
+    }
+
+    pub fn id(&self) -> EntityId {
+        self.get_data().id
+    }
+
+    pub fn to_entity_sprite(&self) -> EntitySprite {
+        EntitySprite {
+            id: self.id(),
+            sprite: self.get_sprite().clone(),
+        }
+    }
+
+    pub fn new_player(config: &GameConfig) -> Self {
+        GameObject::Player(
+            Sprite::Circle(config.window_width / 2.0, config.window_height / 2.0),
+            GameObjectData {
+                velocity: Some((0.0, 0.0)),
+                ..GameObjectData::new(EntityId::PLAYER)
+            },
+        )
+    }
+
+    pub fn new_gate(
+        id: EntityId,
+        coords: &(f64, f64),
+        angle: f64,
+        spin: f64,
+        spawn_time: u64,
+    ) -> Self {
+        GameObject::Gate(
+            Sprite::Triangle(coords.0, coords.1, angle),
+            GameObjectData {
+                id,
+                rotation_speed: Some(spin),
+                spawn_time: Some(spawn_time),
+                ..GameObjectData::default()
+            },
+        )
+    }
+
+    pub fn new_enemy(id: EntityId, coords: &(f64, f64)) -> Self {
+        GameObject::Enemy(Sprite::Diamond(coords.0, coords.1), GameObjectData::new(id))
+    }
+
+    pub fn new_multiplier(
+        id: EntityId,
+        coords: &(f64, f64),
+        velocity: &(f64, f64),
+        spawn_time: u64,
+    ) -> Self {
+        GameObject::Multiplier(
+            Sprite::Square(coords.0, coords.1),
+            GameObjectData {
+                id,
+                velocity: Some(*velocity),
+                spawn_time: Some(spawn_time),
+                ..GameObjectData::default()
+            },
+        )
+    }
+}
+
+impl Entity for GameObject {
+    fn update(&mut self, ctx: &TickContext) {
+        let config = ctx.config;
+
+        match self {
+            GameObject::Gate(sprite, data) => {
+                if let Sprite::Triangle(_, _, rot) = sprite {
+                    *rot += data.rotation_speed.unwrap_or(1.0);
+                }
+            }
+
+            GameObject::Player(sprite, data) => {
+                if let Sprite::Circle(cx, cy) = sprite {
+                    let now = ctx.sim_time_ms;
+                    let wanted = ctx.controls.velocity((*cx, *cy), config);
+                    if ctx.controls.dash_requested {
+                        // Standing still, a dash carries on the way the player was last going
+                        let heading = if wanted == (0.0, 0.0) {
+                            data.heading.unwrap_or_default()
+                        } else {
+                            wanted
+                        };
+                        start_dash(data, heading, now, config);
+                    }
+
+                    let (mut vx, mut vy) = match config.movement {
+                        Movement::Classic => wanted,
+                        Movement::Momentum => {
+                            steer(data.velocity.unwrap_or_default(), wanted, config)
+                        }
+                    };
+
+                    let dashing = data.dash.filter(|dash| now < dash.ends_at);
+                    if let Some(dash) = dashing {
+                        vx = dash.direction.0 * config.dash_speed;
+                        vy = dash.direction.1 * config.dash_speed;
+                    }
+
+                    let x = (*cx + vx).clamp(
+                        config.circle_radius,
+                        config.window_width - config.circle_radius,
+                    );
+                    let y = (*cy + vy).clamp(
+                        config.circle_radius,
+                        config.window_height - config.circle_radius,
+                    );
+
+                    // Hitting a wall kills the speed into it, so there is none to push off later
+                    if x != *cx + vx {
+                        vx = 0.0;
+                    }
+                    if y != *cy + vy {
+                        vy = 0.0;
+                    }
+
+                    (*cx, *cy) = (x, y);
+                    if (vx, vy) != (0.0, 0.0) {
+                        data.heading = Some((vx, vy));
+                    }
+
+                    // Only the dash itself goes faster than `player_speed`; momentum picks up
+                    // from there once it ends
+                    let speed = vx.hypot(vy);
+                    if dashing.is_some() && speed > config.player_speed {
+                        let scale = config.player_speed / speed;
+                        (vx, vy) = (vx * scale, vy * scale);
+                    }
+                    data.velocity = Some((vx, vy));
+                }
+            }
+
+            // Move enemy towards player
+            GameObject::Enemy(sprite, _) => {
+                if let Sprite::Diamond(ex, ey) = sprite {
+                    let (px, py) = ctx.player_position;
+                    let dx = px - *ex;
+                    let dy = py - *ey;
+                    let distance = (dx * dx + dy * dy).sqrt();
+                    if distance > config.enemy_speed {
+                        *ex += dx / distance * config.enemy_speed;
+                        *ey += dy / distance * config.enemy_speed;
+                    }
+                }
+            }
+
+            // Move multiplier using velocity
+            // or towards player
+            GameObject::Multiplier(sprite, data) => {
+                let (px, py) = ctx.player_position;
+
+                if let Sprite::Square(mx, my) = sprite {
+                    if let Some((vx, vy)) = data.velocity.as_mut() {
+                        *mx += *vx;
+                        *my += *vy;
+
+                        // Apply decay to velocity
+                        *vx *= 0.925;
+                        *vy *= 0.925;
+                    }
+
+                    let dx = px - *mx;
+                    let dy = py - *my;
+                    let distance = (dx * dx + dy * dy).sqrt();
+                    if distance < config.multiplier_attract_min {
+                        *mx += dx / distance * config.multiplier_speed;
+                        *my += dy / distance * config.multiplier_speed;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Moves `velocity` at most `player_acceleration` towards `wanted`, or lets it coast down when
+// there is no input
+fn steer(velocity: (f64, f64), wanted: (f64, f64), config: &GameConfig) -> (f64, f64) {
+    let (vx, vy) = velocity;
+
+    if wanted == (0.0, 0.0) {
+        let keep = 1.0 - config.player_friction;
+        let (vx, vy) = (vx * keep, vy * keep);
+
+        // Otherwise it would creep along for ever in ever smaller steps
+        if vx.hypot(vy) < config.player_acceleration * config.player_friction {
+            return (0.0, 0.0);
+        }
+        return (vx, vy);
+    }
+
+    let (dx, dy) = (wanted.0 - vx, wanted.1 - vy);
+    let change = dx.hypot(dy);
+    if change <= config.player_acceleration {
+        return wanted;
+    }
+
+    let scale = config.player_acceleration / change;
+    (vx + dx * scale, vy + dy * scale)
+}
+
+// Does nothing while the last dash is cooling down or without a heading
+fn start_dash(data: &mut GameObjectData, heading: (f64, f64), now: u64, config: &GameConfig) {
+    if data.dash.is_some_and(|dash| now < dash.ready_at) {
+        return;
+    }
+
+    let length = heading.0.hypot(heading.1);
+    if length == 0.0 {
+        return;
+    }
+
+    data.dash = Some(Dash {
+        direction: (heading.0 / length, heading.1 / length),
+        ends_at: now + config.dash_duration_ms,
+        ready_at: now + config.dash_cooldown_ms,
+    });
+}
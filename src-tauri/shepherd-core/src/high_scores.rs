@@ -0,0 +1,163 @@
+use crate::constants::HIGH_SCORE_TABLE_SIZE;
+use crate::game::Game;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+    pub score: u64,
+    pub max_multiplier: u64,
+    pub duration_ms: u64, // simulation time, so pauses don't count
+    pub date: DateTime<Local>,
+    pub seed: u64,
+}
+
+impl HighScoreEntry {
+    pub fn from_game(game: &Game) -> Self {
+        Self {
+            score: game.score,
+            max_multiplier: game.peak_multiplier(),
+            duration_ms: game.sim_time_ms(),
+            date: Local::now(),
+            seed: game.seed(),
+        }
+    }
+}
+
+// Best scores first, capped at HIGH_SCORE_TABLE_SIZE entries
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HighScoreTable {
+    entries: Vec<HighScoreEntry>,
+}
+
+impl HighScoreTable {
+    // A missing file is an empty table rather than an error. Edited files are put back in order
+    // and cut down to size, with ties going to the older entry as in `submit`.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut table: Self = match std::fs::read_to_string(path) {
+            Ok(source) => serde_json::from_str(&source)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+
+        table
+            .entries
+            .sort_by(|a, b| b.score.cmp(&a.score).then(a.date.cmp(&b.date)));
+        table.entries.truncate(HIGH_SCORE_TABLE_SIZE);
+        Ok(table)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn entries(&self) -> &[HighScoreEntry] {
+        &self.entries
+    }
+
+    // Returns the rank the entry landed at, or None if it didn't make the table.
+    // Ties go to the older entry.
+    pub fn submit(&mut self, entry: HighScoreEntry) -> Option<usize> {
+        let rank = self
+            .entries
+            .iter()
+            .position(|existing| entry.score > existing.score)
+            .unwrap_or(self.entries.len());
+
+        if rank >= HIGH_SCORE_TABLE_SIZE {
+            return None;
+        }
+
+        self.entries.insert(rank, entry);
+        self.entries.truncate(HIGH_SCORE_TABLE_SIZE);
+        Some(rank)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Later `age`s are newer
+    fn entry(score: u64, age: i64) -> HighScoreEntry {
+        HighScoreEntry {
+            score,
+            max_multiplier: 1,
+            duration_ms: 0,
+            date: DateTime::from_timestamp(1_700_000_000 + age, 0)
+                .unwrap()
+                .with_timezone(&Local),
+            seed: 0,
+        }
+    }
+
+    fn scores(table: &HighScoreTable) -> Vec<u64> {
+        table.entries().iter().map(|entry| entry.score).collect()
+    }
+
+    #[test]
+    fn submit_ranks_best_first_and_ties_go_to_the_older_entry() {
+        let mut table = HighScoreTable::default();
+        assert_eq!(table.submit(entry(50, 0)), Some(0));
+        assert_eq!(table.submit(entry(200, 1)), Some(0));
+        assert_eq!(table.submit(entry(100, 2)), Some(1));
+        assert_eq!(table.submit(entry(100, 3)), Some(2));
+        assert_eq!(table.submit(entry(50, 4)), Some(4));
+
+        assert_eq!(scores(&table), [200, 100, 100, 50, 50]);
+        assert_eq!(table.entries()[1], entry(100, 2));
+        assert_eq!(table.entries()[3], entry(50, 0));
+    }
+
+    #[test]
+    fn submit_keeps_the_table_to_size() {
+        let mut table = HighScoreTable::default();
+        for i in 0..HIGH_SCORE_TABLE_SIZE as u64 {
+            assert_eq!(table.submit(entry(100 - i * 10, 0)), Some(i as usize));
+        }
+        let lowest = 100 - (HIGH_SCORE_TABLE_SIZE as u64 - 1) * 10;
+
+        // Tying the lowest score isn't enough to get in
+        assert_eq!(table.submit(entry(lowest - 1, 1)), None);
+        assert_eq!(table.submit(entry(lowest, 1)), None);
+        assert_eq!(table.entries().len(), HIGH_SCORE_TABLE_SIZE);
+
+        assert_eq!(
+            table.submit(entry(lowest + 1, 1)),
+            Some(HIGH_SCORE_TABLE_SIZE - 1)
+        );
+        assert_eq!(table.submit(entry(1000, 1)), Some(0));
+        assert_eq!(table.entries().len(), HIGH_SCORE_TABLE_SIZE);
+        assert_eq!(table.entries()[0].score, 1000);
+        assert_eq!(
+            table.entries()[HIGH_SCORE_TABLE_SIZE - 1].score,
+            lowest + 10
+        );
+    }
+
+    #[test]
+    fn load_sorts_and_truncates_edited_files() {
+        let mut entries: Vec<HighScoreEntry> = (0..HIGH_SCORE_TABLE_SIZE as i64 + 2)
+            .map(|age| entry(age as u64 % 4 * 10, age))
+            .collect();
+        entries.reverse();
+
+        let path =
+            std::env::temp_dir().join(format!("shepherd-high-scores-{}.json", std::process::id()));
+        HighScoreTable { entries }.save(&path).unwrap();
+        let table = HighScoreTable::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        let table = table.unwrap();
+
+        assert_eq!(table.entries().len(), HIGH_SCORE_TABLE_SIZE);
+        assert!(table
+            .entries()
+            .windows(2)
+            .all(|pair| pair[0].score > pair[1].score
+                || pair[0].score == pair[1].score && pair[0].date < pair[1].date));
+        assert_eq!(table.entries()[0], entry(30, 3));
+    }
+}
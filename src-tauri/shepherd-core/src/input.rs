@@ -0,0 +1,214 @@
+use crate::config::GameConfig;
+use crate::constants::ANALOG_INPUT_DECIMALS;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+// What a key does in the game, independent of which key it is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Dash,
+    Pause,
+    Retry,         // the same seed again, from the game over screen
+    ReturnToTitle, // when paused or on the game over screen
+}
+
+impl Action {
+    // Held actions steer the simulation and go into replays; the rest are one-off presses
+    pub fn is_held(self) -> bool {
+        !matches!(
+            self,
+            Action::Dash | Action::Pause | Action::Retry | Action::ReturnToTitle
+        )
+    }
+}
+
+// Same names as the serialized form, e.g. "move_up"
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "move_up" => Ok(Action::MoveUp),
+            "move_down" => Ok(Action::MoveDown),
+            "move_left" => Ok(Action::MoveLeft),
+            "move_right" => Ok(Action::MoveRight),
+            "dash" => Ok(Action::Dash),
+            "pause" => Ok(Action::Pause),
+            "retry" => Ok(Action::Retry),
+            "return_to_title" => Ok(Action::ReturnToTitle),
+            _ => Err(format!("unknown action '{}'", s)),
+        }
+    }
+}
+
+// Rounds an analog input the same way whether it is live or read back from a replay
+pub fn quantize(value: f64) -> f64 {
+    let scale = 10f64.powi(ANALOG_INPUT_DECIMALS as i32);
+    (value * scale).round() / scale
+}
+
+// Everything currently steering the player. Keys win over the stick, and the stick over a
+// target, so whichever the player is actively using takes over.
+#[derive(Clone, Debug, Default)]
+pub struct Controls {
+    pub actions: HashSet<Action>,   // held ones only
+    pub stick: (f64, f64),          // gamepad axes, y down, magnitude up to 1
+    pub target: Option<(f64, f64)>, // a point to head for, e.g. under the mouse
+    pub dash_requested: bool,       // until the next tick picks it up
+}
+
+impl Controls {
+    // Movement for one tick from `position`, at most `player_speed` long
+    pub fn velocity(&self, position: (f64, f64), config: &GameConfig) -> (f64, f64) {
+        let (dx, dy) = self.key_velocity(config);
+        if dx != 0.0 || dy != 0.0 {
+            return (dx, dy);
+        }
+
+        let (sx, sy) = self.stick_velocity(config);
+        if sx != 0.0 || sy != 0.0 {
+            return (sx, sy);
+        }
+
+        self.target_velocity(position, config)
+    }
+
+    fn key_velocity(&self, config: &GameConfig) -> (f64, f64) {
+        let mut dx = 0.0;
+        let mut dy = 0.0;
+
+        if self.actions.contains(&Action::MoveUp) {
+            dy -= config.player_speed;
+        }
+
+        if self.actions.contains(&Action::MoveLeft) {
+            dx -= config.player_speed;
+        }
+
+        if self.actions.contains(&Action::MoveDown) {
+            dy += config.player_speed;
+        }
+
+        if self.actions.contains(&Action::MoveRight) {
+            dx += config.player_speed;
+        }
+
+        // Scale speed for diagonal movement
+        let speed_scale = if dx != 0.0 && dy != 0.0 {
+            (2f64).sqrt() / 2.0
+        } else {
+            1.0
+        };
+
+        (dx * speed_scale, dy * speed_scale)
+    }
+
+    // Magnitude inside the dead zone is ignored, and the rest rescaled so speed still ramps up
+    // from 0 at its edge
+    fn stick_velocity(&self, config: &GameConfig) -> (f64, f64) {
+        let (x, y) = self.stick;
+        let magnitude = x.hypot(y);
+        let dead_zone = config.analog_dead_zone;
+        if magnitude <= dead_zone {
+            return (0.0, 0.0);
+        }
+
+        let speed = (magnitude.min(1.0) - dead_zone) / (1.0 - dead_zone) * config.player_speed;
+        (x / magnitude * speed, y / magnitude * speed)
+    }
+
+    // Slows to land on the target rather than overshooting and jittering around it
+    fn target_velocity(&self, position: (f64, f64), config: &GameConfig) -> (f64, f64) {
+        let Some((tx, ty)) = self.target else {
+            return (0.0, 0.0);
+        };
+
+        let (dx, dy) = (tx - position.0, ty - position.1);
+        let distance = dx.hypot(dy);
+        if distance == 0.0 {
+            return (0.0, 0.0);
+        }
+
+        let speed = distance.min(config.player_speed);
+        (dx / distance * speed, dy / distance * speed)
+    }
+}
+
+// Physical key codes (as in `KeyboardEvent.code`, so "KeyW" whatever the layout or shift state)
+// mapped to actions. A key has at most one action, but an action can have several keys.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeyBindings {
+    keys: BTreeMap<String, Action>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let keys = [
+            ("KeyW", Action::MoveUp),
+            ("ArrowUp", Action::MoveUp),
+            ("KeyS", Action::MoveDown),
+            ("ArrowDown", Action::MoveDown),
+            ("KeyA", Action::MoveLeft),
+            ("ArrowLeft", Action::MoveLeft),
+            ("KeyD", Action::MoveRight),
+            ("ArrowRight", Action::MoveRight),
+            ("ShiftLeft", Action::Dash),
+            ("ShiftRight", Action::Dash),
+            ("Space", Action::Pause),
+            ("KeyR", Action::Retry),
+            ("Escape", Action::ReturnToTitle),
+        ];
+
+        Self {
+            keys: keys
+                .into_iter()
+                .map(|(code, action)| (code.to_string(), action))
+                .collect(),
+        }
+    }
+}
+
+impl KeyBindings {
+    // A missing file means the default bindings rather than an error. Actions added since the
+    // file was saved get their default keys, as long as those are still free.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut bindings: Self = match std::fs::read_to_string(path) {
+            Ok(source) => serde_json::from_str(&source)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+
+        for (code, action) in Self::default().keys {
+            if !bindings.keys.values().any(|&bound| bound == action) {
+                bindings.keys.entry(code).or_insert(action);
+            }
+        }
+        Ok(bindings)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn action(&self, code: &str) -> Option<Action> {
+        self.keys.get(code).copied()
+    }
+
+    // Replaces whatever the key was bound to before
+    pub fn bind(&mut self, code: String, action: Action) {
+        self.keys.insert(code, action);
+    }
+
+    pub fn unbind(&mut self, code: &str) {
+        self.keys.remove(code);
+    }
+}
@@ -0,0 +1,15 @@
+pub mod broadphase;
+pub mod collision;
+pub mod config;
+pub mod constants;
+pub mod events;
+pub mod frames;
+pub mod game;
+pub mod game_objects;
+pub mod high_scores;
+pub mod input;
+pub mod phase;
+pub mod replay;
+pub mod sprites;
+pub mod timestep;
+pub mod traits;
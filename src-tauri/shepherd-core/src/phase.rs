@@ -0,0 +1,92 @@
+use serde::Serialize;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum GamePhase {
+    Title,
+    Countdown,
+    Playing,
+    Paused,
+    GameOver,
+}
+
+impl GamePhase {
+    // Whether the game should be ticked at all. A `Countdown` tick only runs down the countdown;
+    // only `Playing` ticks advance the simulation.
+    pub fn is_ticking(self) -> bool {
+        matches!(self, GamePhase::Countdown | GamePhase::Playing)
+    }
+
+    pub fn can_transition_to(self, next: GamePhase) -> bool {
+        use GamePhase::*;
+
+        matches!(
+            (self, next),
+            (Title, Countdown)
+                | (Countdown, Playing)
+                | (Countdown, Paused)
+                | (Playing, Paused)
+                | (Playing, GameOver)
+                | (Paused, Playing)
+                | (Paused, Countdown)
+                | (Paused, Title)
+                | (GameOver, Countdown)
+                | (GameOver, Title)
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PhaseError {
+    pub from: GamePhase,
+    pub to: GamePhase,
+}
+
+impl fmt::Display for PhaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot go from {:?} to {:?}", self.from, self.to)
+    }
+}
+
+impl std::error::Error for PhaseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::GamePhase::{self, *};
+
+    const PHASES: [GamePhase; 5] = [Title, Countdown, Playing, Paused, GameOver];
+
+    #[test]
+    fn only_the_listed_transitions_are_allowed() {
+        let allowed = [
+            (Title, Countdown),
+            (Countdown, Playing),
+            (Countdown, Paused),
+            (Playing, Paused),
+            (Playing, GameOver),
+            (Paused, Playing),
+            (Paused, Countdown),
+            (Paused, Title),
+            (GameOver, Countdown),
+            (GameOver, Title),
+        ];
+
+        for from in PHASES {
+            for to in PHASES {
+                assert_eq!(
+                    from.can_transition_to(to),
+                    allowed.contains(&(from, to)),
+                    "{:?} -> {:?}",
+                    from,
+                    to
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn only_countdown_and_playing_tick() {
+        let ticking: Vec<GamePhase> = PHASES.into_iter().filter(|p| p.is_ticking()).collect();
+        assert_eq!(ticking, [Countdown, Playing]);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::config::GameConfig;
+use crate::game::Game;
+use crate::input::Action;
+
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+
+// Bump whenever the file layout or simulation rules change in a way that breaks old replays
+pub const REPLAY_VERSION: u32 = 5;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    Press(Action), // held actions only
+    Release(Action),
+    Stick(f64, f64), // rounded to ANALOG_INPUT_DECIMALS when applied
+    Target(Option<(f64, f64)>),
+    Dash,
+    Pause,
+    Resume,
+    Config(Box<GameConfig>), // tuning reloaded mid-run
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedInput {
+    pub tick: u64, // number of ticks completed when the input arrived
+    pub event: InputEvent,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub config: GameConfig,
+    pub inputs: Vec<RecordedInput>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, config: GameConfig) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            config,
+            inputs: Vec::new(),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let replay: Replay = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        if replay.version != REPLAY_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "unsupported replay version {} (expected {})",
+                    replay.version, REPLAY_VERSION
+                ),
+            ));
+        }
+
+        // Replay files get shared, so their tuning is checked like any other config file
+        let invalid = |e| io::Error::new(io::ErrorKind::InvalidData, e);
+        replay
+            .config
+            .validate()
+            .map_err(|e| invalid(e.to_string()))?;
+        for input in &replay.inputs {
+            if let InputEvent::Config(config) = &input.event {
+                config
+                    .validate()
+                    .map_err(|e| invalid(format!("at tick {}: {}", input.tick, e)))?;
+            }
+        }
+
+        Ok(replay)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, serde_json::to_string(self)?)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ReplayPlayer {
+    replay: Replay,
+    cursor: usize,
+}
+
+impl ReplayPlayer {
+    // Returns the player together with a fresh game seeded to match the recording. The
+    // countdown never affects the simulation, so playback skips it.
+    pub fn start(replay: Replay) -> (Self, Game) {
+        let mut game = Game::with_config(replay.seed, replay.config.clone());
+        game.start().expect("new games start on the title screen");
+        game.skip_countdown();
+        (Self { replay, cursor: 0 }, game)
+    }
+
+    // Feeds every input recorded for the game's current tick; call before each tick
+    pub fn apply_due(&mut self, game: &mut Game) {
+        while let Some(input) = self.replay.inputs.get(self.cursor) {
+            if input.tick > game.tick_count() {
+                break;
+            }
+
+            // Only accepted inputs are recorded, so they are accepted again here
+            let _ = game.apply_input(input.event.clone());
+            self.cursor += 1;
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.cursor >= self.replay.inputs.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::phase::GamePhase;
+    use crate::sprites::Sprite;
+
+    #[test]
+    fn replaying_a_recording_reproduces_the_game() {
+        let mut game = Game::with_seed(9);
+        game.start().unwrap();
+        game.skip_countdown();
+
+        for tick in 0..1500u64 {
+            match tick {
+                0 => game.press(Action::MoveRight),
+                120 => {
+                    game.release(Action::MoveRight);
+                    game.set_stick(-0.4, 0.8);
+                }
+                200 => game.dash(),
+                260 => {
+                    game.pause().unwrap();
+                    game.set_stick(0.3, -0.9);
+                    game.resume().unwrap();
+                }
+                400 => {
+                    let mut config = game.config().clone();
+                    config.player_speed *= 1.5;
+                    config.enemy_speed *= 0.5;
+                    game.set_config(config);
+                }
+                500 => {
+                    game.set_stick(0.0, 0.0);
+                    game.set_target(Some((100.0, 650.0)));
+                }
+                700 => {
+                    game.set_target(None);
+                    game.press(Action::MoveUp);
+                    game.dash();
+                }
+                800 => game.release(Action::MoveUp),
+                // Head for a gate now and then, so the run scores and sets off explosions
+                tick if tick > 800 && tick % 20 == 0 => {
+                    let gate =
+                        game.get_sprites()
+                            .into_iter()
+                            .find_map(|entity| match entity.sprite {
+                                Sprite::Triangle(x, y, _) => Some((x, y)),
+                                _ => None,
+                            });
+                    game.set_target(gate);
+                }
+                _ => {}
+            }
+
+            game.tick();
+            if game.phase() == GamePhase::GameOver {
+                break;
+            }
+        }
+        assert!(game.tick_count() > 800, "the run ended before every input");
+        assert!(game.score > 0, "no explosion caught an enemy");
+
+        let path =
+            std::env::temp_dir().join(format!("shepherd-replay-{}.json", std::process::id()));
+        game.replay().save(&path).unwrap();
+        let loaded = Replay::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let (mut player, mut replayed) = ReplayPlayer::start(loaded.unwrap());
+        while replayed.tick_count() < game.tick_count() && replayed.phase() == GamePhase::Playing {
+            player.apply_due(&mut replayed);
+            replayed.tick();
+        }
+
+        assert!(player.is_finished());
+        assert_eq!(replayed.tick_count(), game.tick_count());
+        assert_eq!(replayed.score, game.score);
+        assert_eq!(replayed.phase(), game.phase());
+        assert_eq!(replayed.get_sprites(), game.get_sprites());
+    }
+}
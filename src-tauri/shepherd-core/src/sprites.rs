@@ -1,9 +1,9 @@
-use crate::constants::{DIAMOND_RADIUS, SQUARE_RADIUS, TRIANGLE_RADIUS};
+use crate::config::GameConfig;
 use crate::traits::Shape;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Sprite {
     Triangle(f64, f64, f64), // x coordinate, y coordinate, rotation
     Circle(f64, f64),
@@ -23,7 +23,7 @@ impl Shape for Sprite {
         }
     }
 
-    fn get_vertices(&self) -> Vec<(f64, f64)> {
+    fn get_vertices(&self, config: &GameConfig) -> Vec<(f64, f64)> {
         let mut vertices = Vec::new();
 
         match self {
@@ -31,20 +31,20 @@ impl Shape for Sprite {
                 for i in 0..3 {
                     let angle = 2.0 * std::f64::consts::PI / 3.0 * i as f64 + rotation.to_radians();
                     vertices.push((
-                        x + TRIANGLE_RADIUS * angle.cos(),
-                        y + TRIANGLE_RADIUS * angle.sin(),
+                        x + config.triangle_radius * angle.cos(),
+                        y + config.triangle_radius * angle.sin(),
                     ));
                 }
             }
             Sprite::Diamond(x, y) => {
-                vertices.push((*x, y - DIAMOND_RADIUS));
-                vertices.push((x + DIAMOND_RADIUS, *y));
-                vertices.push((*x, y + DIAMOND_RADIUS));
-                vertices.push((x - DIAMOND_RADIUS, *y));
+                vertices.push((*x, y - config.diamond_radius));
+                vertices.push((x + config.diamond_radius, *y));
+                vertices.push((*x, y + config.diamond_radius));
+                vertices.push((x - config.diamond_radius, *y));
             }
             Sprite::Square(x, y) => {
                 // Multiplier has radius 5 in backend so R^2 + R^2 = S^2 => S/2 = sqrt(2) * R / 2  = 7.07/2 = 3.53
-                let s = SQUARE_RADIUS / 2f64.sqrt();
+                let s = config.square_radius / 2f64.sqrt();
                 vertices.push((x - s, y - s));
                 vertices.push((x - s, y + s));
                 vertices.push((x + s, y - s));
@@ -0,0 +1,69 @@
+use std::time::{Duration, Instant};
+
+// Accumulates wall-clock time and hands it out as whole simulation ticks
+#[derive(Clone, Debug)]
+pub struct FixedTimestep {
+    step: Duration,
+    max_catch_up: u32,
+    accumulator: Duration,
+    last_advance: Instant,
+    dropped_ticks: u64,
+}
+
+impl FixedTimestep {
+    pub fn new(step: Duration, max_catch_up: u32) -> Self {
+        Self {
+            step,
+            max_catch_up,
+            accumulator: Duration::ZERO,
+            last_advance: Instant::now(),
+            dropped_ticks: 0,
+        }
+    }
+
+    // Returns how many ticks are due. Anything beyond `max_catch_up` is dropped and counted
+    // rather than simulated, so a long stall can't snowball into an even longer one.
+    pub fn advance(&mut self) -> u32 {
+        let now = Instant::now();
+        self.accumulator += now - self.last_advance;
+        self.last_advance = now;
+
+        let due = (self.accumulator.as_nanos() / self.step.as_nanos()) as u64;
+        self.accumulator -= self.step * due as u32;
+
+        let max_catch_up = u64::from(self.max_catch_up);
+        if due > max_catch_up {
+            self.dropped_ticks += due - max_catch_up;
+        }
+
+        due.min(max_catch_up) as u32
+    }
+
+    // Discards elapsed time, e.g. while paused, so it isn't counted as missed ticks later
+    pub fn idle(&mut self) {
+        self.accumulator = Duration::ZERO;
+        self.last_advance = Instant::now();
+    }
+
+    // How far between the last simulated tick and the next one real time currently is
+    pub fn alpha(&self) -> f64 {
+        self.accumulator.as_secs_f64() / self.step.as_secs_f64()
+    }
+
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+
+    pub fn set_step(&mut self, step: Duration) {
+        self.step = step;
+    }
+
+    pub fn dropped_ticks(&self) -> u64 {
+        self.dropped_ticks
+    }
+
+    pub fn until_next_tick(&self) -> Duration {
+        let pending = self.accumulator + self.last_advance.elapsed();
+        self.step.saturating_sub(pending)
+    }
+}
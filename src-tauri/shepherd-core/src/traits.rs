@@ -0,0 +1,11 @@
+use crate::config::GameConfig;
+use crate::game::TickContext;
+
+pub trait Entity {
+    fn update(&mut self, ctx: &TickContext);
+}
+
+pub trait Shape {
+    fn get_coords(&self) -> (f64, f64);
+    fn get_vertices(&self, config: &GameConfig) -> Vec<(f64, f64)>;
+}
@@ -0,0 +1,224 @@
+mod script;
+
+use crate::script::{parse_script, Command};
+
+use shepherd_core::config::GameConfig;
+use shepherd_core::game::{random_seed, Game};
+use shepherd_core::phase::GamePhase;
+use shepherd_core::replay::{Replay, ReplayPlayer};
+
+use std::process::ExitCode;
+
+// Game time a run stops after without --max-ticks, turned into ticks using the run's own config
+const DEFAULT_MAX_RUN_MS: u64 = 5 * 60 * 1000;
+
+struct Args {
+    script_path: Option<String>,
+    replay_path: Option<String>,
+    record_path: Option<String>,
+    config_path: Option<String>,
+    max_ticks: Option<u64>,
+    seed: Option<u64>,
+    print_events: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut script_path = None;
+    let mut replay_path = None;
+    let mut record_path = None;
+    let mut config_path = None;
+    let mut max_ticks = None;
+    let mut seed = None;
+    let mut print_events = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--max-ticks" => {
+                max_ticks = Some(
+                    args.next()
+                        .ok_or("--max-ticks needs a value")?
+                        .parse()
+                        .map_err(|e| format!("invalid --max-ticks: {}", e))?,
+                );
+            }
+            "--seed" => {
+                seed = Some(
+                    args.next()
+                        .ok_or("--seed needs a value")?
+                        .parse()
+                        .map_err(|e| format!("invalid --seed: {}", e))?,
+                );
+            }
+            "--replay" => replay_path = Some(args.next().ok_or("--replay needs a path")?),
+            "--record" => record_path = Some(args.next().ok_or("--record needs a path")?),
+            "--config" => config_path = Some(args.next().ok_or("--config needs a path")?),
+            "--events" => print_events = true,
+            _ if script_path.is_none() => script_path = Some(arg),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    match (&script_path, &replay_path) {
+        (None, None) => return Err("missing script path".to_string()),
+        (Some(_), Some(_)) => return Err("a script cannot be combined with --replay".to_string()),
+        (None, Some(_)) if seed.is_some() => {
+            return Err("--seed cannot be combined with --replay".to_string())
+        }
+        // Replays carry the config they were recorded with
+        (None, Some(_)) if config_path.is_some() => {
+            return Err("--config cannot be combined with --replay".to_string())
+        }
+        _ => {}
+    }
+
+    Ok(Args {
+        script_path,
+        replay_path,
+        record_path,
+        config_path,
+        max_ticks,
+        seed,
+        print_events,
+    })
+}
+
+fn run_script(
+    path: &str,
+    seed: Option<u64>,
+    config: GameConfig,
+    max_ticks: Option<u64>,
+    print_events: bool,
+) -> Result<Game, String> {
+    let source =
+        std::fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+    let mut events = parse_script(&source)?.into_iter().peekable();
+
+    let max_ticks = max_ticks.unwrap_or_else(|| default_max_ticks(&config));
+
+    // Nobody watches a headless run, so it starts without a countdown
+    let mut game = Game::with_config(seed.unwrap_or_else(random_seed), config);
+    game.start().map_err(|e| e.to_string())?;
+    game.skip_countdown();
+    let mut step = 0;
+
+    loop {
+        while let Some(event) = events.next_if(|event| event.step <= step) {
+            match event.command {
+                Command::Press(action) => game.press(action),
+                Command::Release(action) => game.release(action),
+                Command::Stick(x, y) => game.set_stick(x, y),
+                Command::Target(target) => game.set_target(target),
+                Command::Dash => game.dash(),
+                Command::Pause => game
+                    .pause()
+                    .map_err(|e| format!("step {}: {}", event.step, e))?,
+                Command::Resume => game
+                    .resume()
+                    .map_err(|e| format!("step {}: {}", event.step, e))?,
+            }
+        }
+
+        if game.phase() == GamePhase::Playing {
+            game.tick();
+            report_events(&mut game, print_events);
+        }
+
+        // Nothing left that could unpause us
+        let stalled = game.phase() == GamePhase::Paused && events.peek().is_none();
+        if game.phase() == GamePhase::GameOver || game.tick_count() >= max_ticks || stalled {
+            break;
+        }
+
+        step += 1;
+    }
+
+    Ok(game)
+}
+
+fn run_replay(path: &str, max_ticks: Option<u64>, print_events: bool) -> Result<Game, String> {
+    let replay = Replay::load(path).map_err(|e| format!("failed to load {}: {}", path, e))?;
+    let max_ticks = max_ticks.unwrap_or_else(|| default_max_ticks(&replay.config));
+    let (mut player, mut game) = ReplayPlayer::start(replay);
+
+    loop {
+        player.apply_due(&mut game);
+
+        if game.phase() == GamePhase::Playing {
+            game.tick();
+            report_events(&mut game, print_events);
+        }
+
+        let stalled = game.phase() == GamePhase::Paused && player.is_finished();
+        if game.phase() == GamePhase::GameOver || game.tick_count() >= max_ticks || stalled {
+            break;
+        }
+    }
+
+    Ok(game)
+}
+
+fn default_max_ticks(config: &GameConfig) -> u64 {
+    DEFAULT_MAX_RUN_MS / config.tick_cycle_ms
+}
+
+// Events are always drained so they don't pile up over a long run
+fn report_events(game: &mut Game, print_events: bool) {
+    for event in game.drain_events() {
+        if print_events {
+            println!("{} {:?}", game.tick_count(), event);
+        }
+    }
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let game = match (&args.script_path, &args.replay_path) {
+        (_, Some(path)) => run_replay(path, args.max_ticks, args.print_events)?,
+        (Some(path), None) => {
+            let config = match &args.config_path {
+                Some(config_path) => {
+                    GameConfig::load(config_path).map_err(|e| format!("{}: {}", config_path, e))?
+                }
+                None => GameConfig::default(),
+            };
+            run_script(path, args.seed, config, args.max_ticks, args.print_events)?
+        }
+        (None, None) => unreachable!("checked in parse_args"),
+    };
+
+    if let Some(path) = &args.record_path {
+        game.replay()
+            .save(path)
+            .map_err(|e| format!("failed to write {}: {}", path, e))?;
+    }
+
+    println!(
+        "Seed: {}\nScore: {}\nMultiplier: {}\nLives: {}\nTicks: {}",
+        game.seed(),
+        game.score,
+        game.multiplier,
+        game.lives(),
+        game.tick_count()
+    );
+    if let Some(info) = game.game_over_info() {
+        println!(
+            "Cause: {:?} #{} at ({:.1}, {:.1})\nEnemies destroyed: {}",
+            info.cause, info.entity.0, info.position.0, info.position.1, info.enemies_destroyed
+        );
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let result = parse_args().and_then(run);
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        eprintln!(
+            "usage: shepherd-headless (<script> [--seed N] [--config <file>] | --replay <file>) \
+             [--max-ticks N] [--record <file>] [--events]"
+        );
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,102 @@
+// Input scripts are plain text, one event per line:
+//
+//     # step command [arguments]
+//     0 press move_right
+//     90 release move_right
+//     100 stick 0.5 -0.25
+//     200 target 300 400
+//     300 target none
+//     310 dash
+//     400 pause
+//     520 resume
+//
+// `step` counts driver steps (one per `tick_cycle_ms` of the run's config), so events keep
+// firing while paused.
+
+use shepherd_core::input::Action;
+
+#[derive(Clone, Debug)]
+pub enum Command {
+    Press(Action),
+    Release(Action),
+    Stick(f64, f64),
+    Target(Option<(f64, f64)>),
+    Dash,
+    Pause,
+    Resume,
+}
+
+#[derive(Clone, Debug)]
+pub struct ScriptEvent {
+    pub step: u64,
+    pub command: Command,
+}
+
+pub fn parse_script(source: &str) -> Result<Vec<ScriptEvent>, String> {
+    let mut events = Vec::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let step = parts
+            .next()
+            .unwrap_or("")
+            .parse::<u64>()
+            .map_err(|e| format!("line {}: invalid step: {}", line_number, e))?;
+
+        let command = match (parts.next(), parts.next()) {
+            (Some("press"), Some(action)) => Command::Press(parse_action(action, line_number)?),
+            (Some("release"), Some(action)) => Command::Release(parse_action(action, line_number)?),
+            (Some("stick"), Some(x)) => Command::Stick(
+                parse_number(Some(x), line_number)?,
+                parse_number(parts.next(), line_number)?,
+            ),
+            (Some("target"), Some("none")) => Command::Target(None),
+            (Some("target"), Some(x)) => Command::Target(Some((
+                parse_number(Some(x), line_number)?,
+                parse_number(parts.next(), line_number)?,
+            ))),
+            (Some("dash"), None) => Command::Dash,
+            (Some("pause"), None) => Command::Pause,
+            (Some("resume"), None) => Command::Resume,
+            _ => {
+                return Err(format!(
+                    "line {}: unrecognised command '{}'",
+                    line_number, line
+                ))
+            }
+        };
+
+        if parts.next().is_some() {
+            return Err(format!("line {}: trailing input '{}'", line_number, line));
+        }
+
+        events.push(ScriptEvent { step, command });
+    }
+
+    // Stable sort keeps same-step events in file order
+    events.sort_by_key(|event| event.step);
+    Ok(events)
+}
+
+// Only held actions can be pressed and released; the others have their own commands
+fn parse_action(name: &str, line_number: usize) -> Result<Action, String> {
+    match name.parse::<Action>() {
+        Ok(action) if action.is_held() => Ok(action),
+        Ok(_) => Err(format!("line {}: '{}' cannot be held", line_number, name)),
+        Err(e) => Err(format!("line {}: {}", line_number, e)),
+    }
+}
+
+fn parse_number(value: Option<&str>, line_number: usize) -> Result<f64, String> {
+    let value = value.ok_or_else(|| format!("line {}: missing coordinate", line_number))?;
+    match value.parse::<f64>() {
+        Ok(number) if number.is_finite() => Ok(number),
+        _ => Err(format!("line {}: invalid number '{}'", line_number, value)),
+    }
+}
@@ -1,109 +1,661 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
-#![feature(extract_if)]
 
-mod collision;
-mod constants;
-mod game;
-mod game_objects;
-mod sprites;
-mod traits;
+use shepherd_core::config::GameConfig;
+use shepherd_core::constants::{
+    GameConstants, FRAME_POSITION_DECIMALS, KEYFRAME_INTERVAL, MAX_CATCH_UP_TICKS,
+};
+use shepherd_core::events::GameEvent;
+use shepherd_core::frames::{Frame, FrameEncoder};
+use shepherd_core::game::DashStatus;
+use shepherd_core::high_scores::{HighScoreEntry, HighScoreTable};
+use shepherd_core::input::{Action, KeyBindings};
+use shepherd_core::phase::GamePhase;
+use shepherd_core::replay::Replay;
+use shepherd_core::timestep::FixedTimestep;
 
-use crate::constants::{GameConstants, ENEMY_SPAWN_INTERVAL, GATE_SPAWN_INTERVAL, TICK_CYCLE_MS};
-use crate::game::Game;
-use crate::sprites::Sprite;
-
-use tauri::{async_runtime::RwLock, State, Window};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::Instant;
+use tauri::async_runtime::{JoinHandle, Mutex, RwLock};
+use tauri::{AppHandle, Manager, State, Window, WindowEvent};
+use tokio::sync::{mpsc, oneshot, watch};
 use tokio::time::{sleep, Duration};
 
+mod simulation;
+use simulation::{GameSnapshot, SimCommand, Simulation};
+
+const CONFIG_POLL_INTERVAL_MS: u64 = 500;
+const HIGH_SCORES_FILE: &str = "high_scores.json";
+const KEY_BINDINGS_FILE: &str = "key_bindings.json";
+
 struct AppState {
-    game: RwLock<Game>,
+    commands: mpsc::UnboundedSender<SimCommand>,
+    snapshot: watch::Receiver<GameSnapshot>,
+    event_loop: Mutex<Option<EventLoop>>, // `None` only if a loop task died with the simulation
+    high_scores: RwLock<HighScoreTable>,
+    key_bindings: Mutex<KeyBindings>, // the simulation has its own copy for looking keys up
+}
+
+// The simulation is parked here whenever no loop is driving it, so there is never more than one
+enum EventLoop {
+    Parked(Box<Simulation>),
+    Running {
+        stop: oneshot::Sender<()>,
+        task: JoinHandle<Box<Simulation>>,
+    },
+}
+
+// `alpha` is how far real time has moved past this frame towards the next tick
+#[derive(Serialize)]
+struct SpriteFrame {
+    #[serde(flatten)]
+    frame: Frame,
+    alpha: f64,
+    dash: DashStatus,
+    invulnerable: bool, // from a dash or a respawn
+}
+
+#[derive(Clone, Serialize)]
+struct NewHighScore {
+    rank: usize,
+    entry: HighScoreEntry,
 }
 
+// A reloaded page calls this again, which replaces the loop started by the old one
 #[tauri::command]
-async fn event_loop(state: State<'_, AppState>, window: Window) -> Result<(), tauri::Error> {
-    let mut last_enemy_spawn = std::time::Instant::now();
-    let mut last_gate_spawn = std::time::Instant::now();
+async fn start_event_loop(state: State<'_, AppState>, window: Window) -> Result<(), tauri::Error> {
+    let mut event_loop = state.event_loop.lock().await;
+    stop_event_loop_task(&mut event_loop).await;
+
+    let Some(EventLoop::Parked(sim)) = event_loop.take() else {
+        return Err(
+            std::io::Error::other("the simulation was lost with a failed event loop").into(),
+        );
+    };
+
+    let (stop, stopped) = oneshot::channel();
+    let task = tauri::async_runtime::spawn(run_event_loop(sim, window, stopped));
+    *event_loop = Some(EventLoop::Running { stop, task });
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn stop_event_loop(state: State<'_, AppState>) -> Result<(), tauri::Error> {
+    stop_event_loop_task(&mut *state.event_loop.lock().await).await;
+
+    Ok(())
+}
+
+// Waits for a running loop to finish its current iteration and parks the simulation again
+async fn stop_event_loop_task(event_loop: &mut Option<EventLoop>) {
+    *event_loop = match event_loop.take() {
+        Some(EventLoop::Running { stop, task }) => {
+            // Fails if the loop already ended on its own, which is fine
+            let _ = stop.send(());
+            park(task.await)
+        }
+        parked => parked,
+    };
+}
+
+fn park(finished: tauri::Result<Box<Simulation>>) -> Option<EventLoop> {
+    match finished {
+        Ok(sim) => Some(EventLoop::Parked(sim)),
+        Err(e) => {
+            eprintln!("event loop task failed: {}", e);
+            None
+        }
+    }
+}
+
+async fn run_event_loop(
+    mut sim: Box<Simulation>,
+    window: Window,
+    mut stopped: oneshot::Receiver<()>,
+) -> Box<Simulation> {
+    let result = drive_simulation(&mut sim, &window, &mut stopped).await;
+    if let Err(e) = result {
+        eprintln!("event loop stopped: {}", e);
+    }
+
+    sim
+}
+
+async fn drive_simulation(
+    sim: &mut Simulation,
+    window: &Window,
+    stopped: &mut oneshot::Receiver<()>,
+) -> Result<(), tauri::Error> {
+    let app = window.app_handle();
+    let state = app.state::<AppState>();
+
+    let tick_cycle = Duration::from_millis(sim.game().config().tick_cycle_ms);
+    let mut timestep = FixedTimestep::new(tick_cycle, MAX_CATCH_UP_TICKS);
+    let mut encoder = FrameEncoder::new(KEYFRAME_INTERVAL, Some(FRAME_POSITION_DECIMALS));
+    let mut reported_dropped_ticks = 0;
+    let mut next_publish = Instant::now();
+    let mut unpublished_ticks = false;
+    let mut emitted_config = sim.game().config().clone();
+    let mut events = Vec::new(); // held back until a frame showing them has gone out
+
+    // The page may have missed whatever was sent while no loop was running
+    window.emit("phase_changed", sim.game().phase())?;
 
     loop {
-        {
-            let mut game = state.game.write().await;
+        sim.apply_pending();
 
-            if !game.paused && !game.game_over {
-                if last_enemy_spawn.elapsed() > Duration::from_millis(ENEMY_SPAWN_INTERVAL) {
-                    game.spawn_enemy();
-                    last_enemy_spawn = std::time::Instant::now();
-                }
+        // Follow tick length and publish rate changes from config reloads
+        let tick_cycle = Duration::from_millis(sim.game().config().tick_cycle_ms);
+        if timestep.step() != tick_cycle {
+            timestep.set_step(tick_cycle);
+        }
+        let publish_interval = Duration::from_secs_f64(1.0 / sim.game().config().publish_rate_hz);
 
-                if last_gate_spawn.elapsed() > Duration::from_millis(GATE_SPAWN_INTERVAL) {
-                    game.spawn_gate();
-                    last_gate_spawn = std::time::Instant::now();
+        if sim.game().phase().is_ticking() {
+            let due_ticks = timestep.advance();
+            for _ in 0..due_ticks {
+                if !sim.step() {
+                    break;
                 }
-                game.tick();
-                window.emit("update_sprites", &game.get_sprites())?;
+                unpublished_ticks = true;
+            }
+        } else {
+            timestep.idle();
+        }
 
-                // and now check for explosions
-                for (bx, by) in &game.pending_boom_locations {
-                    window.emit("explode", Sprite::Point(*bx, *by))?;
-                }
-                game.pending_boom_locations.clear();
+        sim.publish_snapshot();
+        events.extend(sim.drain_events());
+        let phase_changed = events
+            .iter()
+            .any(|event| matches!(event, GameEvent::PhaseChanged { .. }));
+
+        // New games, replays and reloads can all change the tuning the frontend draws with
+        if sim.game().config() != &emitted_config {
+            emitted_config = sim.game().config().clone();
+            emit_game_constants(&app, &emitted_config)?;
+        }
+
+        // A frame owed from before a phase change goes out straight away, so e.g. the game over
+        // screen shows where the run really ended
+        let now = Instant::now();
+        if unpublished_ticks && (now >= next_publish || phase_changed) {
+            let frame = SpriteFrame {
+                frame: encoder.encode(&sim.game().get_sprites()),
+                alpha: timestep.alpha(),
+                dash: sim.game().dash_status(),
+                invulnerable: sim.game().player_invulnerable(),
+            };
+            window.emit("update_sprites", &frame)?;
+
+            // Emit score, multiplier and lives updates to the frontend
+            window.emit(
+                "update_score_multiplier",
+                (sim.game().score, sim.game().multiplier, sim.game().lives()),
+            )?;
+
+            next_publish = now + publish_interval;
+            unpublished_ticks = false;
+        }
+
+        // Everything that happened up to the last frame, explosions included. With no ticks owed,
+        // e.g. after pausing, there is no frame to wait for.
+        if !unpublished_ticks {
+            for event in events.drain(..) {
+                match event {
+                    GameEvent::PhaseChanged { phase } => {
+                        if let (GamePhase::GameOver, Some(info)) =
+                            (phase, sim.game().game_over_info())
+                        {
+                            window.emit("game_over", info)?;
+
+                            // Only live runs count towards the table
+                            if !sim.is_replaying() {
+                                let entry = HighScoreEntry::from_game(sim.game());
+                                if let Err(e) = submit_entry(&app, &state, entry).await {
+                                    eprintln!("failed to record high score: {}", e);
+                                }
+                            }
+                        }
 
-                // Emit score and multiplier updates to the frontend
-                window.emit("update_score_multiplier", (&game.score, &game.multiplier))?;
+                        window.emit("phase_changed", phase)?;
+                    }
+                    event => window.emit("game_event", event)?,
+                }
             }
         }
 
-        sleep(Duration::from_millis(TICK_CYCLE_MS)).await;
+        if timestep.dropped_ticks() > reported_dropped_ticks {
+            window.emit(
+                "dropped_ticks",
+                timestep.dropped_ticks() - reported_dropped_ticks,
+            )?;
+            reported_dropped_ticks = timestep.dropped_ticks();
+        }
+
+        // Wake for whichever comes first: the next tick or a publish that is still owed
+        let mut wait = timestep.until_next_tick();
+        if unpublished_ticks {
+            wait = wait.min(next_publish.saturating_duration_since(Instant::now()));
+        }
+        tokio::select! {
+            _ = sleep(wait) => {}
+            // Also resolves if the sender is dropped, so a lost handle can't leave the loop running
+            _ = &mut *stopped => return Ok(()),
+        }
     }
 }
 
+// Title -> Countdown
 #[tauri::command]
-async fn handle_spacebar(state: State<'_, AppState>) -> Result<(), tauri::Error> {
-    let mut game = state.game.write().await;
+async fn start_game(state: State<'_, AppState>) -> Result<(), tauri::Error> {
+    send_command(&state, SimCommand::Start);
 
-    if game.game_over {
-        game.reset_game();
-    } else {
-        game.paused = !game.paused;
-    }
+    Ok(())
+}
+
+// Playing -> Paused
+#[tauri::command]
+async fn pause_game(state: State<'_, AppState>) -> Result<(), tauri::Error> {
+    send_command(&state, SimCommand::Pause);
+
+    Ok(())
+}
+
+// Paused -> Playing
+#[tauri::command]
+async fn resume_game(state: State<'_, AppState>) -> Result<(), tauri::Error> {
+    send_command(&state, SimCommand::Resume);
+
+    Ok(())
+}
+
+// GameOver -> Countdown, on a new seed unless one was set
+#[tauri::command]
+async fn restart_game(state: State<'_, AppState>) -> Result<(), tauri::Error> {
+    send_command(&state, SimCommand::Restart);
+
+    Ok(())
+}
+
+// GameOver -> Countdown on the seed that was just played
+#[tauri::command]
+async fn retry_game(state: State<'_, AppState>) -> Result<(), tauri::Error> {
+    send_command(&state, SimCommand::Retry);
+
+    Ok(())
+}
+
+// Paused or GameOver -> Title
+#[tauri::command]
+async fn return_to_title(state: State<'_, AppState>) -> Result<(), tauri::Error> {
+    send_command(&state, SimCommand::ReturnToTitle);
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_phase(state: State<'_, AppState>) -> Result<GamePhase, tauri::Error> {
+    let snapshot = state.snapshot.borrow();
+
+    Ok(snapshot.phase)
+}
+
+// `code` is a `KeyboardEvent.code`, so the bindings see physical keys
+#[tauri::command]
+async fn key_down(state: State<'_, AppState>, code: String) -> Result<(), tauri::Error> {
+    send_command(&state, SimCommand::KeyDown(code));
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn key_up(state: State<'_, AppState>, code: String) -> Result<(), tauri::Error> {
+    send_command(&state, SimCommand::KeyUp(code));
+
+    Ok(())
+}
+
+// Gamepad axes, sent whenever they change
+#[tauri::command]
+async fn set_stick(state: State<'_, AppState>, x: f64, y: f64) -> Result<(), tauri::Error> {
+    send_command(&state, SimCommand::Stick(x, y));
+
+    Ok(())
+}
+
+// The point the player steers towards, or `None` to stop following it
+#[tauri::command]
+async fn set_move_target(
+    state: State<'_, AppState>,
+    target: Option<(f64, f64)>,
+) -> Result<(), tauri::Error> {
+    send_command(&state, SimCommand::Target(target));
 
     Ok(())
 }
 
-// Update the move_player command to key_down and key_up
 #[tauri::command]
-async fn key_down(state: State<'_, AppState>, key: String) -> Result<(), tauri::Error> {
-    let mut game = state.game.write().await;
-    game.key_down(key);
+async fn get_key_bindings(state: State<'_, AppState>) -> Result<KeyBindings, tauri::Error> {
+    let key_bindings = state.key_bindings.lock().await;
+
+    Ok(key_bindings.clone())
+}
+
+#[tauri::command]
+async fn bind_key(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    code: String,
+    action: Action,
+) -> Result<(), tauri::Error> {
+    let mut key_bindings = state.key_bindings.lock().await;
+    key_bindings.bind(code, action);
+
+    apply_key_bindings(&app, &state, &key_bindings)
+}
+
+#[tauri::command]
+async fn unbind_key(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    code: String,
+) -> Result<(), tauri::Error> {
+    let mut key_bindings = state.key_bindings.lock().await;
+    key_bindings.unbind(&code);
+
+    apply_key_bindings(&app, &state, &key_bindings)
+}
+
+#[tauri::command]
+async fn reset_key_bindings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), tauri::Error> {
+    let mut key_bindings = state.key_bindings.lock().await;
+    *key_bindings = KeyBindings::default();
+
+    apply_key_bindings(&app, &state, &key_bindings)
+}
+
+// Saves the bindings and hands them to the simulation and every window
+fn apply_key_bindings(
+    app: &AppHandle,
+    state: &AppState,
+    key_bindings: &KeyBindings,
+) -> Result<(), tauri::Error> {
+    let dir = app_config_dir(app)?;
+    std::fs::create_dir_all(&dir)?;
+    key_bindings.save(dir.join(KEY_BINDINGS_FILE))?;
+
+    send_command(state, SimCommand::SetBindings(key_bindings.clone()));
+    app.emit_all("update_key_bindings", key_bindings)
+}
+
+#[tauri::command]
+async fn get_seed(state: State<'_, AppState>) -> Result<u64, tauri::Error> {
+    let snapshot = state.snapshot.borrow();
+
+    Ok(snapshot.seed)
+}
+
+#[tauri::command]
+async fn set_seed(state: State<'_, AppState>, seed: u64) -> Result<(), tauri::Error> {
+    send_command(&state, SimCommand::NewGame(seed));
 
     Ok(())
 }
 
+// Writes the current run to the app data directory and returns the file path
 #[tauri::command]
-async fn key_up(state: State<'_, AppState>, key: String) -> Result<(), tauri::Error> {
-    let mut game = state.game.write().await;
-    game.key_up(key);
+async fn save_replay(app: AppHandle, state: State<'_, AppState>) -> Result<String, tauri::Error> {
+    let replay = current_replay(&state).await?;
+
+    let dir = app_data_dir(&app)?.join("replays");
+    std::fs::create_dir_all(&dir)?;
+
+    let file_name = format!(
+        "{}-{}.json",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        replay.seed
+    );
+    let path = dir.join(file_name);
+    replay.save(&path)?;
+
+    Ok(path.to_string_lossy().into_owned())
+}
+
+// Asks a running loop's simulation for its recording, or reads it straight from a parked one
+async fn current_replay(state: &AppState) -> Result<Replay, tauri::Error> {
+    let mut event_loop = state.event_loop.lock().await;
+    let lost = || std::io::Error::other("the simulation was lost with a failed event loop");
+
+    if let Some(EventLoop::Running { task, .. }) = &mut *event_loop {
+        let (reply, replay) = oneshot::channel();
+        send_command(state, SimCommand::GetReplay(reply));
+
+        // A loop that ends on its own never gets to the command, so rather than wait forever,
+        // park what it hands back and read that instead
+        let finished = tokio::select! {
+            replay = replay => return Ok(replay.map_err(|_| lost())?),
+            finished = task => finished,
+        };
+        *event_loop = park(finished);
+    }
+
+    match &*event_loop {
+        Some(EventLoop::Parked(sim)) => Ok(sim.game().replay().clone()),
+        _ => Err(lost().into()),
+    }
+}
+
+#[tauri::command]
+async fn play_replay(state: State<'_, AppState>, path: String) -> Result<(), tauri::Error> {
+    send_command(&state, SimCommand::PlayReplay(Replay::load(path)?));
 
     Ok(())
 }
 
 #[tauri::command]
-fn get_game_constants() -> GameConstants {
-    GameConstants::new()
+async fn get_high_scores(state: State<'_, AppState>) -> Result<Vec<HighScoreEntry>, tauri::Error> {
+    let high_scores = state.high_scores.read().await;
+
+    Ok(high_scores.entries().to_vec())
+}
+
+// Returns the rank the entry landed at, if it made the table
+#[tauri::command]
+async fn submit_high_score(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    entry: HighScoreEntry,
+) -> Result<Option<usize>, tauri::Error> {
+    submit_entry(&app, &state, entry).await
+}
+
+async fn submit_entry(
+    app: &AppHandle,
+    state: &AppState,
+    entry: HighScoreEntry,
+) -> Result<Option<usize>, tauri::Error> {
+    let mut high_scores = state.high_scores.write().await;
+
+    let rank = high_scores.submit(entry.clone());
+    if let Some(rank) = rank {
+        let dir = app_data_dir(app)?;
+        std::fs::create_dir_all(&dir)?;
+        high_scores.save(dir.join(HIGH_SCORES_FILE))?;
+        app.emit_all("new_high_score", NewHighScore { rank, entry })?;
+    }
+
+    Ok(rank)
+}
+
+// Rendering sizes for whatever config the running game uses, which may come from a replay
+#[tauri::command]
+async fn get_game_constants(state: State<'_, AppState>) -> Result<GameConstants, tauri::Error> {
+    let snapshot = state.snapshot.borrow();
+
+    Ok(GameConstants::new(&snapshot.config))
+}
+
+// The simulation owns the receiving end for the life of the app, so this can't fail
+fn send_command(state: &AppState, command: SimCommand) {
+    state
+        .commands
+        .send(command)
+        .expect("simulation command queue closed");
+}
+
+fn emit_game_constants(app: &AppHandle, config: &GameConfig) -> Result<(), tauri::Error> {
+    app.emit_all("update_game_constants", GameConstants::new(config))
+}
+
+fn app_data_dir(app: &AppHandle) -> Result<PathBuf, std::io::Error> {
+    app.path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no app data directory"))
+}
+
+fn app_config_dir(app: &AppHandle) -> Result<PathBuf, std::io::Error> {
+    app.path_resolver()
+        .app_config_dir()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no app config directory"))
+}
+
+// SHEPHERD_CONFIG overrides the default of config.toml in the app config directory
+fn config_path(app: &AppHandle) -> Option<PathBuf> {
+    std::env::var_os("SHEPHERD_CONFIG")
+        .map(PathBuf::from)
+        .or_else(|| Some(app.path_resolver().app_config_dir()?.join("config.toml")))
+}
+
+fn load_config(app: &AppHandle) -> GameConfig {
+    match config_path(app) {
+        Some(path) if path.exists() => GameConfig::load(&path).unwrap_or_else(|e| {
+            eprintln!("{}: {}, using defaults", path.display(), e);
+            GameConfig::default()
+        }),
+        _ => GameConfig::default(),
+    }
+}
+
+// Polls the config file and pushes edits into the running game on its next tick
+async fn watch_config(app: AppHandle, path: PathBuf) {
+    let modified_time = |path: &PathBuf| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let mut last_modified = modified_time(&path);
+
+    loop {
+        sleep(Duration::from_millis(CONFIG_POLL_INTERVAL_MS)).await;
+
+        let modified = modified_time(&path);
+        if modified == last_modified {
+            continue;
+        }
+        last_modified = modified;
+
+        // A deleted file keeps the current tuning
+        if modified.is_none() {
+            continue;
+        }
+
+        let config = match GameConfig::load(&path) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("{}: {}, keeping previous config", path.display(), e);
+                continue;
+            }
+        };
+
+        send_command(&app.state::<AppState>(), SimCommand::SetConfig(config));
+    }
 }
 
 fn main() {
     tauri::Builder::default()
+        .setup(|app| {
+            let handle = app.handle();
+            let config = load_config(&handle);
+            let high_scores = match app_data_dir(&handle) {
+                Ok(dir) => HighScoreTable::load(dir.join(HIGH_SCORES_FILE)).unwrap_or_else(|e| {
+                    eprintln!("failed to load high scores: {}", e);
+                    HighScoreTable::default()
+                }),
+                Err(_) => HighScoreTable::default(),
+            };
+            let key_bindings = match app_config_dir(&handle) {
+                Ok(dir) => KeyBindings::load(dir.join(KEY_BINDINGS_FILE)).unwrap_or_else(|e| {
+                    eprintln!("failed to load key bindings: {}", e);
+                    KeyBindings::default()
+                }),
+                Err(_) => KeyBindings::default(),
+            };
+            let (simulation, commands, snapshot) = Simulation::new(config, key_bindings.clone());
+            app.manage(AppState {
+                commands,
+                snapshot,
+                event_loop: Mutex::new(Some(EventLoop::Parked(Box::new(simulation)))),
+                high_scores: RwLock::new(high_scores),
+                key_bindings: Mutex::new(key_bindings),
+            });
+
+            if let Some(path) = config_path(&handle) {
+                tauri::async_runtime::spawn(watch_config(handle, path));
+            }
+            Ok(())
+        })
+        .on_window_event(|event| match event.event() {
+            // Nothing is left to draw to, so don't keep simulating
+            WindowEvent::Destroyed => {
+                let app = event.window().app_handle();
+                tauri::async_runtime::spawn(async move {
+                    let state = app.state::<AppState>();
+                    stop_event_loop_task(&mut *state.event_loop.lock().await).await;
+                });
+            }
+            WindowEvent::Focused(focused) => {
+                let state = event.window().state::<AppState>();
+                let command = if *focused {
+                    SimCommand::FocusGained
+                } else {
+                    SimCommand::FocusLost
+                };
+                send_command(&state, command);
+            }
+            // Not every platform unfocuses a window on minimize
+            WindowEvent::Resized(_) if event.window().is_minimized().unwrap_or(false) => {
+                let state = event.window().state::<AppState>();
+                send_command(&state, SimCommand::FocusLost);
+            }
+            _ => {}
+        })
         .invoke_handler(tauri::generate_handler![
-            event_loop,
+            start_event_loop,
+            stop_event_loop,
             key_up,
             key_down,
-            handle_spacebar,
+            set_stick,
+            set_move_target,
+            get_key_bindings,
+            bind_key,
+            unbind_key,
+            reset_key_bindings,
+            start_game,
+            pause_game,
+            resume_game,
+            restart_game,
+            retry_game,
+            return_to_title,
+            get_phase,
+            get_seed,
+            set_seed,
+            save_replay,
+            play_replay,
+            get_high_scores,
+            submit_high_score,
             get_game_constants
         ])
-        .manage(AppState {
-            game: RwLock::new(Game::new()),
-        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,304 @@
+use shepherd_core::config::GameConfig;
+use shepherd_core::events::GameEvent;
+use shepherd_core::game::{random_seed, Game};
+use shepherd_core::input::{Action, KeyBindings};
+use shepherd_core::phase::{GamePhase, PhaseError};
+use shepherd_core::replay::{Replay, ReplayPlayer};
+
+use std::collections::BTreeSet;
+use tokio::sync::{mpsc, oneshot, watch};
+
+// Everything the rest of the app can ask the simulation to do. Commands queue up and are applied
+// right before the next tick, so each input lands on (and is recorded against) exactly one tick.
+#[derive(Debug)]
+pub enum SimCommand {
+    KeyDown(String), // physical key codes, looked up in the bindings
+    KeyUp(String),
+    Stick(f64, f64),
+    Target(Option<(f64, f64)>), // in game coordinates
+    Start,
+    Pause,
+    Resume,
+    Restart,
+    Retry, // like `Restart`, but always on the same seed
+    ReturnToTitle,
+    NewGame(u64),
+    PlayReplay(Replay),
+    SetConfig(GameConfig), // reloaded from disk
+    SetBindings(KeyBindings),
+    FocusLost, // also sent when the window is minimized
+    FocusGained,
+    GetReplay(oneshot::Sender<Replay>), // the run so far, as `save_replay` writes it
+}
+
+// Read-only view of the game for commands that only need to look at it
+#[derive(Clone, Debug)]
+pub struct GameSnapshot {
+    pub seed: u64,
+    pub phase: GamePhase,
+    pub config: GameConfig,
+}
+
+// Sole owner of the `Game`. Only the event loop drives it; everything else goes through the
+// command queue or reads the latest snapshot.
+pub struct Simulation {
+    game: Game,
+    replay_player: Option<ReplayPlayer>,
+    config: GameConfig, // applied to new games
+    commands: mpsc::UnboundedReceiver<SimCommand>,
+    snapshots: watch::Sender<GameSnapshot>,
+    auto_paused: bool, // paused by `FocusLost` rather than the player
+    bindings: KeyBindings,
+    held_keys: BTreeSet<String>, // bound to held actions
+    events: Vec<GameEvent>,      // left undrained by games that have since been replaced
+}
+
+impl Simulation {
+    pub fn new(
+        config: GameConfig,
+        bindings: KeyBindings,
+    ) -> (
+        Self,
+        mpsc::UnboundedSender<SimCommand>,
+        watch::Receiver<GameSnapshot>,
+    ) {
+        let game = Game::with_config(random_seed(), config.clone());
+        let (command_sender, commands) = mpsc::unbounded_channel();
+        let (snapshots, snapshot_receiver) = watch::channel(GameSnapshot {
+            seed: game.seed(),
+            phase: game.phase(),
+            config: game.config().clone(),
+        });
+
+        let simulation = Self {
+            game,
+            replay_player: None,
+            config,
+            commands,
+            snapshots,
+            auto_paused: false,
+            bindings,
+            held_keys: BTreeSet::new(),
+            events: Vec::new(),
+        };
+        (simulation, command_sender, snapshot_receiver)
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    // Everything since the last call, across any games that were swapped in meanwhile
+    pub fn drain_events(&mut self) -> Vec<GameEvent> {
+        let mut events = std::mem::take(&mut self.events);
+        events.extend(self.game.drain_events());
+        events
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.replay_player.is_some()
+    }
+
+    // Applies every queued command and any recorded replay inputs due on the current tick
+    pub fn apply_pending(&mut self) {
+        while let Ok(command) = self.commands.try_recv() {
+            self.handle(command);
+        }
+
+        if let Some(player) = self.replay_player.as_mut() {
+            player.apply_due(&mut self.game);
+        }
+    }
+
+    // Returns false without ticking if, once pending inputs are in, the phase doesn't tick
+    pub fn step(&mut self) -> bool {
+        self.apply_pending();
+        if !self.game.phase().is_ticking() {
+            return false;
+        }
+
+        self.game.tick();
+        true
+    }
+
+    fn handle(&mut self, command: SimCommand) {
+        if let Err(e) = self.try_handle(command) {
+            eprintln!("ignoring command: {}", e);
+        }
+    }
+
+    fn try_handle(&mut self, command: SimCommand) -> Result<(), PhaseError> {
+        match command {
+            SimCommand::KeyDown(code) => match self.bindings.action(&code) {
+                Some(Action::Pause) => self.toggle_pause()?,
+                Some(Action::Retry) if self.game.phase() == GamePhase::GameOver => {
+                    self.try_handle(SimCommand::Retry)?
+                }
+                Some(Action::ReturnToTitle)
+                    if matches!(self.game.phase(), GamePhase::Paused | GamePhase::GameOver) =>
+                {
+                    self.try_handle(SimCommand::ReturnToTitle)?
+                }
+                // Elsewhere these keys are free to mean nothing
+                Some(Action::Retry | Action::ReturnToTitle) => {}
+                Some(Action::Dash) if !self.is_replaying() => self.game.dash(),
+                Some(action) if !self.is_replaying() => {
+                    self.held_keys.insert(code);
+                    self.game.press(action);
+                }
+                _ => {}
+            },
+            SimCommand::KeyUp(code) => {
+                // Another key may still be holding the same action down
+                if let Some(action) = self.bindings.action(&code).filter(|a| a.is_held()) {
+                    self.held_keys.remove(&code);
+                    if !self.is_replaying() && !self.is_held(action) {
+                        self.game.release(action);
+                    }
+                }
+            }
+            SimCommand::Stick(..) | SimCommand::Target(_) if self.is_replaying() => {}
+            SimCommand::Stick(x, y) => self.game.set_stick(x, y),
+            SimCommand::Target(target) => self.game.set_target(target),
+            SimCommand::Start => self.game.start()?,
+            SimCommand::Pause => self.game.pause()?,
+            SimCommand::Resume => self.game.resume()?,
+            // Leaving a replay goes to a new live game rather than restarting the recording
+            SimCommand::Restart if self.is_replaying() => {
+                self.game.check_restart()?;
+                self.leave_replay(GamePhase::Countdown, random_seed())?;
+                self.game.start()?;
+            }
+            SimCommand::Restart => self.game.restart()?,
+            // Retrying a replay plays its seed live
+            SimCommand::Retry if self.is_replaying() => {
+                self.game.check_restart()?;
+                self.leave_replay(GamePhase::Countdown, self.game.seed())?;
+                self.game.start()?;
+            }
+            SimCommand::Retry => self.game.retry()?,
+            SimCommand::ReturnToTitle if self.is_replaying() => {
+                self.leave_replay(GamePhase::Title, random_seed())?
+            }
+            SimCommand::ReturnToTitle => self.game.return_to_title()?,
+            SimCommand::NewGame(seed) => {
+                self.replay_player = None;
+                self.replace_game(Game::with_config(seed, self.config.clone()));
+                self.game.pin_seed();
+            }
+            SimCommand::PlayReplay(replay) => {
+                let (player, game) = ReplayPlayer::start(replay);
+                self.replay_player = Some(player);
+                self.replace_game(game);
+            }
+            SimCommand::SetConfig(config) => {
+                self.config = config.clone();
+
+                // Replays keep the tuning they were recorded with
+                if !self.is_replaying() {
+                    self.game.set_config(config);
+                }
+            }
+            // Nobody waiting any more is fine
+            SimCommand::GetReplay(reply) => {
+                let _ = reply.send(self.game.replay().clone());
+            }
+            SimCommand::SetBindings(bindings) => {
+                // Keys held now may come up bound to something else
+                self.bindings = bindings;
+                self.release_all();
+            }
+            // Replays play on regardless, since nobody is steering
+            SimCommand::FocusLost | SimCommand::FocusGained if self.is_replaying() => {}
+            SimCommand::FocusLost => {
+                // Key ups for anything held now would go to another window
+                self.release_all();
+                if matches!(self.game.phase(), GamePhase::Countdown | GamePhase::Playing) {
+                    self.game.pause()?;
+                    self.auto_paused = true;
+                }
+            }
+            SimCommand::FocusGained => {
+                let auto_paused = std::mem::take(&mut self.auto_paused);
+                if auto_paused
+                    && self.game.phase() == GamePhase::Paused
+                    && self.game.config().resume_on_focus
+                {
+                    self.game.resume_after_countdown()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // What the pause key means depends on where the game is; nothing during a countdown
+    fn toggle_pause(&mut self) -> Result<(), PhaseError> {
+        let command = match self.game.phase() {
+            GamePhase::Title => SimCommand::Start,
+            GamePhase::Countdown => return Ok(()),
+            GamePhase::Playing => SimCommand::Pause,
+            GamePhase::Paused => SimCommand::Resume,
+            GamePhase::GameOver => SimCommand::Restart,
+        };
+        self.try_handle(command)
+    }
+
+    fn is_held(&self, action: Action) -> bool {
+        self.held_keys
+            .iter()
+            .any(|code| self.bindings.action(code) == Some(action))
+    }
+
+    fn release_all(&mut self) {
+        self.held_keys.clear();
+        if !self.is_replaying() {
+            self.game.release_all();
+        }
+    }
+
+    // Swaps the replay for a new live game on `seed` at the title screen, as long as the replay
+    // itself could move to `to` from where it is
+    fn leave_replay(&mut self, to: GamePhase, seed: u64) -> Result<(), PhaseError> {
+        let from = self.game.phase();
+        if !from.can_transition_to(to) {
+            return Err(PhaseError { from, to });
+        }
+
+        self.replay_player = None;
+        self.replace_game(Game::with_config(seed, self.config.clone()));
+        Ok(())
+    }
+
+    // Keeps every undrained event, and reports a phase the new game is in without having said
+    // so, e.g. the title screen of a fresh one
+    fn replace_game(&mut self, mut game: Game) {
+        self.events.extend(self.game.drain_events());
+        self.events.extend(game.drain_events());
+
+        let reported = self
+            .events
+            .iter()
+            .rev()
+            .find_map(|event| match event {
+                GameEvent::PhaseChanged { phase } => Some(*phase),
+                _ => None,
+            })
+            .unwrap_or(self.game.phase());
+        if game.phase() != reported {
+            self.events.push(GameEvent::PhaseChanged {
+                phase: game.phase(),
+            });
+        }
+
+        self.game = game;
+    }
+
+    pub fn publish_snapshot(&mut self) {
+        self.snapshots.send_replace(GameSnapshot {
+            seed: self.game.seed(),
+            phase: self.game.phase(),
+            config: self.game.config().clone(),
+        });
+    }
+}